@@ -1,40 +1,33 @@
+use partial_functional::collections::MonoidStack;
 use partial_functional::prelude::*;
 
+// Every element on a MonoidStack remembers the minimum below it, so popping never has to search for the new one
 #[derive(Debug)]
 struct MinStack {
-    stack: Vec<Min<i32>>,
-    min: Min<i32>,
+    stack: MonoidStack<Min<i32>>,
 }
 
 impl MinStack {
     fn new() -> Self {
         Self {
-            stack: Vec::new(),
-            min: Min::empty(),
+            stack: MonoidStack::new(),
         }
     }
 
     fn push(&mut self, val: i32) {
-        self.min = self.min.combine(val.into());
         self.stack.push(val.into());
     }
 
     fn pop(&mut self) -> i32 {
-        let top = self.stack.pop().unwrap();
-
-        if top == self.min {
-            self.min = self.stack.iter().fold(Min::empty(), |min, &x| min.combine(x));
-        }
-
-        top.0
+        self.stack.pop().unwrap().0
     }
 
     fn top(&self) -> i32 {
-        self.stack[self.stack.len() - 1].0
+        self.stack.peek().unwrap().0
     }
 
     fn get_min(&self) -> i32 {
-        self.min.0
+        self.stack.aggregate().copied().unwrap_or_else(Min::empty).0
     }
 }
 
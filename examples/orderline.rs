@@ -1,7 +1,6 @@
 // This example was translated from an F# blog at https://fsharpforfunandprofit.com/posts/monoids-part2/
 // Note that the blog is very old but the information is still good.
 
-use partfun_derive::Semigroup;
 use partial_functional::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -18,20 +17,27 @@ struct TotalLine {
     total: Sum<f32>,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+impl From<ProductLine> for TotalLine {
+    fn from(line: ProductLine) -> Self {
+        Self {
+            quantity: line.quantity,
+            total: line.line_total,
+        }
+    }
+}
+
+// By implementing Default and Semigroup, OrderLine gets a blanket implementation of Monoid.
+// Any product line is turned into a total line before it is combined, and Empty is the identity.
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd, Semigroup)]
 enum OrderLine {
+    #[semigroup(into = Total)]
     Product(ProductLine),
     Total(TotalLine),
+    #[default]
+    #[semigroup(identity)]
     Empty,
 }
 
-// By implementing Default and Semigroup, OrderLine gets a blanket implementation of Monoid
-impl Default for OrderLine {
-    fn default() -> Self {
-        Self::Empty
-    }
-}
-
 impl std::fmt::Display for OrderLine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -46,27 +52,6 @@ impl std::fmt::Display for OrderLine {
     }
 }
 
-impl Semigroup for OrderLine {
-    fn combine(self, other: Self) -> Self {
-        match (self, other) {
-            (OrderLine::Total(a), OrderLine::Total(b)) => Self::Total(a.combine(b)),
-            (OrderLine::Empty, a) | (a, OrderLine::Empty) => a,
-            (OrderLine::Product(a), OrderLine::Total(b)) => Self::Total(b.combine(TotalLine {
-                quantity: a.quantity,
-                total: a.line_total,
-            })),
-            (OrderLine::Total(a), OrderLine::Product(b)) => Self::Total(a.combine(TotalLine {
-                quantity: b.quantity,
-                total: b.line_total,
-            })),
-            (OrderLine::Product(a), OrderLine::Product(b)) => Self::Total(TotalLine {
-                quantity: a.quantity.combine(b.quantity),
-                total: a.line_total.combine(b.line_total),
-            }),
-        }
-    }
-}
-
 fn main() {
     let product_lines = vec![
         OrderLine::Product(ProductLine {
@@ -89,17 +74,21 @@ fn main() {
         }),
     ];
 
+    // running_combine yields the total after every line, so the last one is the total of the whole order
     let mut total = product_lines
         .into_iter()
         .inspect(|x| println!("{}", x))
-        .fold(OrderLine::empty(), |tot, item| tot.combine(item)); // OrderLine::empty() and OrderLine::default() is interchangable
+        .running_combine()
+        .inspect(|x| println!("  Running: {}", x))
+        .last()
+        .unwrap_or_default(); // OrderLine::empty() and OrderLine::default() is interchangable
 
     println!("{}", total);
 
     let new_line = OrderLine::Product(ProductLine {
         code: "DDD".into(),
         quantity: 1.into(),
-        price: 29.98.into(),
+        price: 29.98,
         line_total: 29.98.into(),
     });
     println!("Adding order: {new_line}");
@@ -0,0 +1,124 @@
+use proc_macro2::{Span, TokenStream};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, token, Attribute, Expr, ExprLit, Ident, Lit, LitStr, Path, Token};
+
+/// A single argument inside of an attribute like `#[semigroup(identity, into = Total)]` or
+/// `#[partial(derive(Debug))]`.
+pub(crate) struct Arg {
+    pub name: Ident,
+    pub value: Option<Expr>,
+    pub list: Option<TokenStream>,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let mut value = None;
+        let mut list = None;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            value = Some(input.parse()?);
+        } else if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            list = Some(content.parse()?);
+        }
+
+        Ok(Self { name, value, list })
+    }
+}
+
+impl Arg {
+    pub fn is(&self, name: &str) -> bool {
+        self.name == name
+    }
+
+    /// Checks that the argument is a plain flag like `identity`.
+    pub fn flag(&self) -> syn::Result<()> {
+        match (&self.value, &self.list) {
+            (None, None) => Ok(()),
+            _ => Err(syn::Error::new(
+                self.name.span(),
+                format!("`{}` doesn't take a value", self.name),
+            )),
+        }
+    }
+
+    /// The value of an argument like `into = Total`.
+    pub fn path(&self) -> syn::Result<&Path> {
+        match &self.value {
+            Some(Expr::Path(expr)) if expr.qself.is_none() => Ok(&expr.path),
+            _ => Err(syn::Error::new(
+                self.name.span(),
+                format!("expected `{} = ...` with a path", self.name),
+            )),
+        }
+    }
+
+    /// The value of an argument like `mixed = "left"`.
+    pub fn str(&self) -> syn::Result<&LitStr> {
+        match &self.value {
+            Some(Expr::Lit(ExprLit { lit: Lit::Str(lit), .. })) => Ok(lit),
+            _ => Err(syn::Error::new(
+                self.name.span(),
+                format!("expected `{} = \"...\"` with a string", self.name),
+            )),
+        }
+    }
+
+    /// The value of an argument like `empty = Vec::new()`.
+    pub fn expr(&self) -> syn::Result<&Expr> {
+        match &self.value {
+            Some(expr) => Ok(expr),
+            None => Err(syn::Error::new(
+                self.name.span(),
+                format!("expected `{} = ...` with an expression", self.name),
+            )),
+        }
+    }
+
+    /// The tokens of an argument like `derive(Debug, Clone)`.
+    pub fn list(&self) -> syn::Result<&TokenStream> {
+        match &self.list {
+            Some(list) => Ok(list),
+            None => Err(syn::Error::new(
+                self.name.span(),
+                format!("expected `{}(...)`", self.name),
+            )),
+        }
+    }
+
+    pub fn unknown(&self, namespace: &str, expected: &[&str]) -> syn::Error {
+        let expected = expected
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        syn::Error::new(
+            self.name.span(),
+            format!(
+                "unknown {} attribute `{}` here, expected one of {}",
+                namespace, self.name, expected
+            ),
+        )
+    }
+}
+
+/// Collects the arguments of every `#[namespace(...)]` attribute.
+pub(crate) fn args(attrs: &[Attribute], namespace: &str) -> syn::Result<Vec<Arg>> {
+    let mut args = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(namespace)) {
+        let parsed = attr.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?;
+        args.extend(parsed);
+    }
+
+    Ok(args)
+}
+
+pub(crate) fn error<T>(span: Span, message: impl std::fmt::Display) -> syn::Result<T> {
+    Err(syn::Error::new(span, message))
+}
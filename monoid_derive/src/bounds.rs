@@ -0,0 +1,56 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{Generics, Ident, Token, WherePredicate};
+
+use crate::attrs::Arg;
+
+/// Parses the predicates of an attribute like `#[semigroup(bound = "T: Ord")]`, an empty string means no bounds.
+pub(crate) fn parse(arg: &Arg) -> syn::Result<Vec<WherePredicate>> {
+    let predicates = arg
+        .str()?
+        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+
+    Ok(predicates.into_iter().collect())
+}
+
+pub(crate) fn type_params(generics: &Generics) -> Vec<Ident> {
+    generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect()
+}
+
+/// Whether `ty` uses any of the type parameters, only those types need a bound, any other type either implements
+/// the trait or fails to compile at the field itself.
+pub(crate) fn mentions(ty: &impl ToTokens, params: &[Ident]) -> bool {
+    fn walk(tokens: TokenStream, params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.contains(&ident),
+            TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    walk(ty.to_token_stream(), params)
+}
+
+/// Adds `predicates` to the where clause of `generics`, skipping any duplicates.
+pub(crate) fn with_predicates(mut generics: Generics, predicates: Vec<WherePredicate>) -> Generics {
+    let where_clause = generics.make_where_clause();
+    let mut seen = where_clause
+        .predicates
+        .iter()
+        .map(|predicate| predicate.to_token_stream().to_string())
+        .collect::<Vec<_>>();
+
+    for predicate in predicates {
+        let key = predicate.to_token_stream().to_string();
+        if !seen.contains(&key) {
+            seen.push(key);
+            where_clause.predicates.push(predicate);
+        }
+    }
+
+    generics
+}
@@ -0,0 +1,379 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Attribute, DataEnum, Fields, Ident, Path, Variant, WherePredicate};
+
+use crate::attrs::{self, error};
+use crate::bounds;
+use crate::fields::FieldConfig;
+
+/// How two different variants are combined when neither of them is converted into the other.
+enum Mixed {
+    /// Keep the left hand side.
+    Left,
+    /// Keep the right hand side.
+    Right,
+    /// Keep the variant that is declared first.
+    Priority,
+}
+
+struct Config<'a> {
+    variants: Vec<VariantConfig<'a>>,
+    identity: Option<&'a Ident>,
+    mixed: Option<Mixed>,
+}
+
+struct VariantConfig<'a> {
+    variant: &'a Variant,
+    identity: bool,
+    into: Option<Ident>,
+}
+
+impl<'a> Config<'a> {
+    fn parse(ident: &Ident, attrs: &[Attribute], data: &'a DataEnum) -> syn::Result<Self> {
+        if data.variants.is_empty() {
+            return error(ident.span(), format!("`{}` has no variants to combine", ident));
+        }
+
+        let mut mixed = None;
+        for arg in attrs::args(attrs, "semigroup")? {
+            // The bounds are read along with the bounds of a struct.
+            if arg.is("bound") {
+                continue;
+            }
+            if !arg.is("mixed") {
+                return Err(arg.unknown("semigroup", &["mixed", "bound"]));
+            }
+
+            let lit = arg.str()?;
+            mixed = Some(match lit.value().as_str() {
+                "left" => Mixed::Left,
+                "right" => Mixed::Right,
+                "priority" => Mixed::Priority,
+                _ => return error(lit.span(), "expected `mixed` to be one of \"left\", \"right\" or \"priority\""),
+            });
+        }
+
+        let mut variants = Vec::new();
+        let mut identity = None;
+        for variant in &data.variants {
+            let mut config = VariantConfig {
+                variant,
+                identity: false,
+                into: None,
+            };
+
+            for arg in attrs::args(&variant.attrs, "semigroup")? {
+                if arg.is("identity") {
+                    arg.flag()?;
+                    if !matches!(variant.fields, Fields::Unit) {
+                        return error(
+                            variant.ident.span(),
+                            format!("`{}` has fields, only a unit variant can be the identity", variant.ident),
+                        );
+                    }
+                    if identity.is_some() {
+                        return error(arg.name.span(), "there can only be one identity variant");
+                    }
+                    config.identity = true;
+                    identity = Some(&variant.ident);
+                } else if arg.is("into") {
+                    let path = arg.path()?;
+                    match path.get_ident() {
+                        Some(target) => config.into = Some(target.clone()),
+                        None => return error(path.span(), "expected the name of a variant of this enum"),
+                    }
+                } else {
+                    return Err(arg.unknown("semigroup", &["identity", "into"]));
+                }
+            }
+
+            if config.identity && config.into.is_some() {
+                return error(
+                    variant.ident.span(),
+                    "the identity variant can't be converted into another variant",
+                );
+            }
+
+            variants.push(config);
+        }
+
+        let config = Self {
+            variants,
+            identity,
+            mixed,
+        };
+        config.validate(ident)?;
+
+        Ok(config)
+    }
+
+    fn validate(&self, ident: &Ident) -> syn::Result<()> {
+        for config in &self.variants {
+            let Some(target) = &config.into else { continue };
+
+            let Some(target_config) = self.variants.iter().find(|other| other.variant.ident == *target) else {
+                return error(target.span(), format!("`{}` has no variant named `{}`", ident, target));
+            };
+
+            if target_config.identity || target_config.into.is_some() {
+                return error(
+                    target.span(),
+                    format!("`{}` has to be combined itself to be converted into", target),
+                );
+            }
+
+            for variant in [config.variant, target_config.variant] {
+                if !single_field(&variant.fields) {
+                    return error(
+                        variant.ident.span(),
+                        format!(
+                            "`{}` needs exactly one unnamed field, like `{}(Value)`, to convert between variants",
+                            variant.ident, variant.ident
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Keeping one side is only associative when nothing else is combined, `A(1) + B + A(2)` would give `A(2)`
+        // or `A(1)` depending on the grouping.
+        let side = match self.mixed {
+            Some(Mixed::Left) => Some("left"),
+            Some(Mixed::Right) => Some("right"),
+            _ => None,
+        };
+        if let Some(side) = side {
+            if let Some(config) = self.combined().find(|config| !matches!(config.variant.fields, Fields::Unit)) {
+                return error(
+                    config.variant.ident.span(),
+                    format!(
+                        "`{}` has fields, `mixed = \"{}\"` can only be used when every combined variant is a unit \
+                         variant, use `mixed = \"priority\"` instead",
+                        config.variant.ident, side
+                    ),
+                );
+            }
+        }
+
+        let combined = self.combined().count();
+        if combined > 1 && self.mixed.is_none() {
+            return error(
+                ident.span(),
+                format!(
+                    "`{}` has {} variants that can be combined with each other, choose what happens when they meet \
+                     with #[semigroup(mixed = \"left\" | \"right\" | \"priority\")] or convert them with \
+                     #[semigroup(into = Variant)]",
+                    ident, combined
+                ),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The bounds of every field that is combined, and a `From` bound for every conversion between variants.
+    fn bounds(&self, krate: &Path, params: &[Ident]) -> syn::Result<Vec<WherePredicate>> {
+        let mut predicates = Vec::new();
+
+        for config in self.combined() {
+            for field in &config.variant.fields {
+                predicates.extend(FieldConfig::parse(field)?.semigroup_bound(krate, field, params));
+            }
+        }
+
+        for config in &self.variants {
+            let Some(target) = &config.into else { continue };
+            let target = self.variants.iter().find(|other| other.variant.ident == *target).unwrap();
+
+            let from = &config.variant.fields.iter().next().unwrap().ty;
+            let into = &target.variant.fields.iter().next().unwrap().ty;
+            if bounds::mentions(from, params) || bounds::mentions(into, params) {
+                predicates.push(parse_quote!(#into: ::core::convert::From<#from>));
+            }
+        }
+
+        Ok(predicates)
+    }
+
+    /// The variants that are neither the identity nor converted into another variant.
+    fn combined(&self) -> impl Iterator<Item = &VariantConfig<'a>> {
+        self.variants
+            .iter()
+            .filter(|config| !config.identity && config.into.is_none())
+    }
+}
+
+fn single_field(fields: &Fields) -> bool {
+    matches!(fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+}
+
+/// Builds `combine` for an enum, the identity is dropped, variants marked with `into` are converted next, equal
+/// variants are combined field by field and anything else is decided by the `mixed` rule.
+pub(crate) fn combine(
+    krate: &Path,
+    ident: &Ident,
+    attrs: &[Attribute],
+    data: &DataEnum,
+    params: &[Ident],
+) -> syn::Result<(TokenStream, Vec<WherePredicate>)> {
+    let config = Config::parse(ident, attrs, data)?;
+    let predicates = config.bounds(krate, params)?;
+
+    let conversions = config
+        .variants
+        .iter()
+        .filter_map(|config| Some((&config.variant.ident, config.into.as_ref()?)))
+        .map(|(from, into)| {
+            quote! {
+                Self::#from(value) => Self::#into(::core::convert::From::from(value)),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let convert = if conversions.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            let convert = |value: Self| -> Self {
+                match value {
+                    #( #conversions )*
+                    value => value,
+                }
+            };
+            let (lhs, rhs) = (convert(lhs), convert(rhs));
+        }
+    };
+
+    // The identity is checked before any conversion, so that combining with it gives back the exact same value.
+    let identity = config.identity.map(|identity| {
+        quote! {
+            if let Self::#identity = lhs {
+                return rhs;
+            }
+            if let Self::#identity = rhs {
+                return lhs;
+            }
+        }
+    });
+
+    let same = config
+        .combined()
+        .map(|config| same_variant(krate, config.variant))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mixed = match config.mixed {
+        Some(Mixed::Left) => quote! {
+            #[allow(unreachable_patterns)]
+            (lhs, _) => lhs,
+        },
+        Some(Mixed::Right) => quote! {
+            #[allow(unreachable_patterns)]
+            (_, rhs) => rhs,
+        },
+        Some(Mixed::Priority) => {
+            let ranks = config.variants.iter().enumerate().map(|(rank, config)| {
+                let variant = &config.variant.ident;
+                quote!(Self::#variant { .. } => #rank,)
+            });
+
+            quote! {
+                #[allow(unreachable_patterns)]
+                (lhs, rhs) => {
+                    let rank = |value: &Self| -> usize {
+                        match value {
+                            #( #ranks )*
+                        }
+                    };
+
+                    if rank(&lhs) <= rank(&rhs) { lhs } else { rhs }
+                }
+            }
+        }
+        None => quote! {
+            #[allow(unreachable_patterns)]
+            _ => ::core::unreachable!("the identity is handled and other variants are converted before they're combined"),
+        },
+    };
+
+    let combine_fn = quote! {
+        fn combine(self, rhs: Self) -> Self {
+            let lhs = self;
+            #identity
+            #convert
+
+            match (lhs, rhs) {
+                #( #same )*
+                #mixed
+            }
+        }
+    };
+
+    Ok((combine_fn, predicates))
+}
+
+fn same_variant(krate: &Path, variant: &Variant) -> syn::Result<TokenStream> {
+    let name = &variant.ident;
+
+    Ok(match &variant.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| f.ident.as_ref().unwrap()).collect::<Vec<_>>();
+            let lhs = names.iter().map(|name| format_ident!("lhs_{}", name)).collect::<Vec<_>>();
+            let rhs = names.iter().map(|name| format_ident!("rhs_{}", name)).collect::<Vec<_>>();
+            let combined = fields.named.iter().zip(&lhs).zip(&rhs).map(|((f, lhs), rhs)| {
+                let name = &f.ident;
+                let combined = FieldConfig::parse(f)?.combine(krate, f, quote!(#lhs), quote!(#rhs));
+                Ok(quote_spanned! {f.span()=>
+                    #name: #combined
+                })
+            }).collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                (Self::#name { #( #names: #lhs ),* }, Self::#name { #( #names: #rhs ),* }) => {
+                    Self::#name { #( #combined ),* }
+                }
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let lhs = (0..fields.unnamed.len()).map(|i| format_ident!("lhs_{}", i)).collect::<Vec<_>>();
+            let rhs = (0..fields.unnamed.len()).map(|i| format_ident!("rhs_{}", i)).collect::<Vec<_>>();
+            let combined = fields.unnamed.iter().zip(&lhs).zip(&rhs).map(|((f, lhs), rhs)| {
+                Ok(FieldConfig::parse(f)?.combine(krate, f, quote!(#lhs), quote!(#rhs)))
+            }).collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                (Self::#name( #( #lhs ),* ), Self::#name( #( #rhs ),* )) => Self::#name( #( #combined ),* ),
+            }
+        }
+        Fields::Unit => quote! {
+            (Self::#name, Self::#name) => Self::#name,
+        },
+    })
+}
+
+/// Builds `empty` for an enum, which is the variant marked with `#[semigroup(identity)]`.
+pub(crate) fn empty(ident: &Ident, data: &DataEnum) -> syn::Result<TokenStream> {
+    let mut identity = None;
+    for variant in &data.variants {
+        for arg in attrs::args(&variant.attrs, "semigroup")? {
+            if arg.is("identity") {
+                identity = Some(&variant.ident);
+            }
+        }
+    }
+
+    match identity {
+        Some(identity) => Ok(quote! {
+            fn empty() -> Self {
+                Self::#identity
+            }
+        }),
+        None => error(
+            ident.span(),
+            format!(
+                "deriving Monoid for `{}` needs a unit variant marked with #[semigroup(identity)]",
+                ident
+            ),
+        ),
+    }
+}
@@ -0,0 +1,179 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Expr, Field, Ident, Path, WherePredicate};
+
+use crate::attrs::{self, error};
+use crate::bounds;
+
+/// How a single field is combined.
+enum Strategy {
+    /// Combine the field with its own [Semigroup] implementation.
+    Combine,
+    /// Call `path(lhs, rhs)`.
+    With(Path),
+    /// Keep the left hand side.
+    First,
+    /// Keep the right hand side.
+    Last,
+    /// Leave the field out and use its default value.
+    Skip,
+    /// Wrap both sides in `path`, combine them and unwrap the result.
+    Via(Path),
+}
+
+pub(crate) struct FieldConfig {
+    strategy: Strategy,
+    /// The attribute that chose the strategy, if any.
+    chosen_by: Option<Ident>,
+    empty: Option<Expr>,
+    semigroup_bound: Option<Vec<WherePredicate>>,
+    monoid_bound: Option<Vec<WherePredicate>>,
+}
+
+impl FieldConfig {
+    pub fn parse(field: &Field) -> syn::Result<Self> {
+        let mut strategy = None;
+        let mut chosen_by = None;
+        let mut semigroup_bound = None;
+        for arg in attrs::args(&field.attrs, "semigroup")? {
+            if arg.is("bound") {
+                semigroup_bound = Some(bounds::parse(&arg)?);
+                continue;
+            }
+
+            let next = if arg.is("with") {
+                Strategy::With(arg.path()?.clone())
+            } else if arg.is("via") {
+                Strategy::Via(arg.path()?.clone())
+            } else if arg.is("first") {
+                arg.flag()?;
+                Strategy::First
+            } else if arg.is("last") {
+                arg.flag()?;
+                Strategy::Last
+            } else if arg.is("skip") {
+                arg.flag()?;
+                Strategy::Skip
+            } else {
+                return Err(arg.unknown("semigroup", &["with", "via", "first", "last", "skip", "bound"]));
+            };
+
+            if strategy.replace(next).is_some() {
+                return error(
+                    arg.name.span(),
+                    "a field can only use one of `with`, `via`, `first`, `last` or `skip`",
+                );
+            }
+            chosen_by = Some(arg.name.clone());
+        }
+
+        let mut empty = None;
+        let mut monoid_bound = None;
+        for arg in attrs::args(&field.attrs, "monoid")? {
+            if arg.is("bound") {
+                monoid_bound = Some(bounds::parse(&arg)?);
+            } else if !arg.is("empty") {
+                return Err(arg.unknown("monoid", &["empty", "bound"]));
+            } else if empty.replace(arg.expr()?.clone()).is_some() {
+                return error(arg.name.span(), "`empty` can only be given once");
+            }
+        }
+
+        Ok(Self {
+            strategy: strategy.unwrap_or(Strategy::Combine),
+            chosen_by,
+            empty,
+            semigroup_bound,
+            monoid_bound,
+        })
+    }
+
+    /// The expression combining the two values of the field.
+    pub fn combine(&self, krate: &Path, field: &Field, lhs: TokenStream, rhs: TokenStream) -> TokenStream {
+        match &self.strategy {
+            Strategy::Combine => quote_spanned! {field.span()=> #krate::semigroup::Semigroup::combine(#lhs, #rhs) },
+            Strategy::With(path) => quote_spanned! {path.span()=> #path(#lhs, #rhs) },
+            Strategy::First => quote! {{ let _ = #rhs; #lhs }},
+            Strategy::Last => quote! {{ let _ = #lhs; #rhs }},
+            Strategy::Skip => quote! {{ let _ = (#lhs, #rhs); ::core::default::Default::default() }},
+            Strategy::Via(path) => quote_spanned! {path.span()=>
+                #krate::semigroup::Semigroup::combine(#path(#lhs), #path(#rhs)).0
+            },
+        }
+    }
+
+    /// Checks that the field has an identity under its strategy. Keeping one side or skipping the field never leaves
+    /// the other side as it is, and only the `empty` value given along with a `with` function can be its identity.
+    pub fn check_monoid(&self) -> syn::Result<()> {
+        let Some(name) = &self.chosen_by else {
+            return Ok(());
+        };
+
+        match &self.strategy {
+            Strategy::First | Strategy::Last | Strategy::Skip => error(
+                name.span(),
+                format!(
+                    "a field with `{}` has no identity, so `Monoid` can't be derived, use a type like `First`, \
+                     `Last` or `Option` that is a `Monoid` instead",
+                    name
+                ),
+            ),
+            Strategy::With(path) if self.empty.is_none() => error(
+                path.span(),
+                "a field combined `with` a function needs `#[monoid(empty = ...)]`, the value that the function \
+                 leaves the other side unchanged with, to derive `Monoid`",
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// The expression for the field of the identity.
+    pub fn empty(&self, krate: &Path, field: &Field) -> TokenStream {
+        match (&self.empty, &self.strategy) {
+            (Some(expr), _) => quote_spanned! {expr.span()=> #expr },
+            (None, Strategy::Via(path)) => quote_spanned! {path.span()=>
+                <#path<_> as #krate::monoid::Monoid>::empty().0
+            },
+            (None, _) => quote_spanned! {field.span()=> #krate::monoid::Monoid::empty() },
+        }
+    }
+
+    /// The bounds needed to combine the field, given by `#[semigroup(bound = "...")]` or else inferred from the
+    /// field type when it uses any of `params`.
+    pub fn semigroup_bound(&self, krate: &Path, field: &Field, params: &[Ident]) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.semigroup_bound {
+            return bound.clone();
+        }
+
+        let ty = &field.ty;
+        if !bounds::mentions(ty, params) {
+            return Vec::new();
+        }
+
+        match &self.strategy {
+            Strategy::Combine => vec![parse_quote!(#ty: #krate::semigroup::Semigroup)],
+            Strategy::Via(path) => vec![parse_quote!(#path<#ty>: #krate::semigroup::Semigroup)],
+            Strategy::Skip => vec![parse_quote!(#ty: ::core::default::Default)],
+            Strategy::With(_) | Strategy::First | Strategy::Last => Vec::new(),
+        }
+    }
+
+    /// The bounds needed for the identity of the field, given by `#[monoid(bound = "...")]` or else inferred from
+    /// the field type when it uses any of `params`.
+    pub fn monoid_bound(&self, krate: &Path, field: &Field, params: &[Ident]) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.monoid_bound {
+            return bound.clone();
+        }
+
+        let ty = &field.ty;
+        if self.empty.is_some() || !bounds::mentions(ty, params) {
+            return Vec::new();
+        }
+
+        match &self.strategy {
+            Strategy::Via(path) => vec![parse_quote!(#path<#ty>: #krate::monoid::Monoid)],
+            _ => vec![parse_quote!(#ty: #krate::monoid::Monoid)],
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DataStruct, DeriveInput, Fields, GenericArgument, GenericParam, Ident, Path, PathArguments,
+    Type, WherePredicate,
+};
+
+use crate::attrs::error;
+use crate::bounds;
+
+/// Everything needed to map the fields that use the mapped parameter `from` into fields that use `to`.
+struct Mapper<'a> {
+    krate: &'a Path,
+    from: &'a Ident,
+    to: Ident,
+    predicates: Vec<WherePredicate>,
+}
+
+impl Mapper<'_> {
+    /// Builds the expression that maps `expr` of type `ty`, a field of the parameter itself is passed to `f` and a
+    /// `FunctorMut` over it is mapped with `fmap_mut`, which recurses into its own parameter.
+    fn map(&mut self, ty: &Type, expr: TokenStream) -> syn::Result<TokenStream> {
+        let from = std::slice::from_ref(self.from);
+        if !bounds::mentions(ty, from) {
+            return Ok(expr);
+        }
+
+        match ty {
+            Type::Paren(paren) => self.map(&paren.elem, expr),
+            Type::Group(group) => self.map(&group.elem, expr),
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident(self.from) => Ok(quote!(f(#expr))),
+            Type::Path(path) if path.qself.is_none() => {
+                let segment = path.path.segments.last().unwrap();
+                if segment.ident == "PhantomData" {
+                    return Ok(quote!(::core::marker::PhantomData));
+                }
+
+                let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                    return self.unsupported(ty);
+                };
+                // A functor maps a single one of its parameters, so only one of them can use the mapped parameter.
+                let mut mentioning = args.args.iter().filter(|arg| bounds::mentions(arg, from));
+                let (Some(GenericArgument::Type(inner)), None) = (mentioning.next(), mentioning.next()) else {
+                    return self.unsupported(ty);
+                };
+
+                let mapped = self.map(inner, quote!(value))?;
+                let krate = self.krate;
+                let inner_to = self.substitute(inner);
+                let target = self.substitute(ty);
+                self.predicates.push(parse_quote! {
+                    #ty: #krate::functor::FunctorMut<#inner, #inner_to>
+                        + #krate::hkt::HKT<#inner, #inner_to, Target = #target>
+                });
+
+                Ok(quote! {
+                    <#ty as #krate::functor::FunctorMut<#inner, #inner_to>>::fmap_mut(#expr, |value| #mapped)
+                })
+            }
+            _ => self.unsupported(ty),
+        }
+    }
+
+    /// `ty` with every use of the mapped parameter replaced by the parameter it is mapped into.
+    fn substitute<T: ToTokens + syn::parse::Parse>(&self, ty: &T) -> T {
+        fn walk(tokens: TokenStream, from: &Ident, to: &Ident) -> TokenStream {
+            tokens
+                .into_iter()
+                .map(|token| match token {
+                    TokenTree::Ident(ident) if ident == *from => TokenTree::Ident(to.clone()),
+                    TokenTree::Group(group) => {
+                        let mut replaced = Group::new(group.delimiter(), walk(group.stream(), from, to));
+                        replaced.set_span(group.span());
+                        TokenTree::Group(replaced)
+                    }
+                    token => token,
+                })
+                .collect()
+        }
+
+        syn::parse2(walk(ty.to_token_stream(), self.from, &self.to)).expect("replacing an identifier keeps the syntax")
+    }
+
+    fn unsupported<T>(&self, ty: &Type) -> syn::Result<T> {
+        error(
+            ty.span(),
+            format!(
+                "can't map this field, only a field of type `{}` or a FunctorMut over it, like `Vec<{}>`, can be mapped",
+                self.from, self.from
+            ),
+        )
+    }
+}
+
+/// Derives `HKT` and `FunctorMut` over the last type parameter of a struct or an enum.
+pub(crate) fn expand(krate: &Path, input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        ident, data, generics, ..
+    } = input;
+
+    let Some(from) = generics.type_params().last().map(|param| param.ident.clone()) else {
+        return error(
+            ident.span(),
+            format!(
+                "`{}` has no type parameter, FunctorMut maps over the last type parameter",
+                ident
+            ),
+        );
+    };
+
+    // The parameter that is mapped into, named so it doesn't clash with any other parameter.
+    let params = bounds::type_params(&generics);
+    let mut to = format_ident!("B");
+    while params.contains(&to) {
+        to = format_ident!("{}_", to);
+    }
+
+    let mut mapper = Mapper {
+        krate,
+        from: &from,
+        to: to.clone(),
+        predicates: Vec::new(),
+    };
+
+    // The mapped parameter gets the same bounds as the parameter it replaces, so that the target is a valid type.
+    let mut target_generics = generics.clone();
+    let mut to_param = None;
+    for param in target_generics.type_params_mut() {
+        if param.ident == from {
+            param.ident = to.clone();
+            param.bounds = param.bounds.iter().map(|bound| mapper.substitute(bound)).collect();
+            to_param = Some(param.clone());
+        }
+    }
+    let (_, target_ty_generics, _) = target_generics.split_for_impl();
+    let turbofish = target_ty_generics.as_turbofish();
+
+    let body = match &data {
+        Data::Struct(DataStruct { fields, .. }) => {
+            let (pattern, construct) = destructure(&mut mapper, fields)?;
+            quote! {
+                let Self #pattern = self;
+                #ident #turbofish #construct
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let name = &variant.ident;
+                    let (pattern, construct) = destructure(&mut mapper, &variant.fields)?;
+                    Ok(quote!(Self::#name #pattern => #ident #turbofish::#name #construct,))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                match self {
+                    #( #arms )*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return error(
+                data.union_token.span(),
+                "FunctorMut can't be derived for a union, only for structs and enums",
+            )
+        }
+    };
+
+    // Whatever the declaration asks of the mapped parameter is asked of the parameter it's mapped into as well.
+    let mut impl_generics = generics.clone();
+    impl_generics.params.push(GenericParam::Type(to_param.unwrap()));
+    let copied = generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| &where_clause.predicates)
+        .filter(|predicate| bounds::mentions(predicate, std::slice::from_ref(&from)))
+        .map(|predicate| mapper.substitute(predicate))
+        .collect();
+    let impl_generics = bounds::with_predicates(impl_generics, copied);
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (hkt_impl_generics, _, hkt_where_clause) = impl_generics.split_for_impl();
+
+    let functor_generics = bounds::with_predicates(impl_generics.clone(), mapper.predicates);
+    let (functor_impl_generics, _, functor_where_clause) = functor_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #hkt_impl_generics #krate::hkt::HKT<#from, #to> for #ident #ty_generics #hkt_where_clause {
+            type URI = Self;
+            type Target = #ident #target_ty_generics;
+        }
+
+        impl #functor_impl_generics #krate::functor::FunctorMut<#from, #to> for #ident #ty_generics #functor_where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn fmap_mut<F: ::core::ops::FnMut(#from) -> #to>(self, mut f: F) -> <Self as #krate::hkt::HKT<#from, #to>>::Target {
+                #body
+            }
+        }
+    })
+}
+
+/// The pattern that binds every field and the fields that build the mapped value from those bindings. The bindings
+/// are renamed so that no field can shadow `f`.
+fn destructure(mapper: &mut Mapper, fields: &Fields) -> syn::Result<(TokenStream, TokenStream)> {
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let names = fields
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap())
+                .collect::<Vec<_>>();
+            let bindings = names
+                .iter()
+                .map(|name| format_ident!("field_{}", name))
+                .collect::<Vec<_>>();
+            let mapped = fields
+                .named
+                .iter()
+                .zip(&bindings)
+                .map(|(f, binding)| mapper.map(&f.ty, quote!(#binding)))
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            (quote!({ #( #names: #bindings ),* }), quote!({ #( #names: #mapped ),* }))
+        }
+        Fields::Unnamed(fields) => {
+            let bindings = (0..fields.unnamed.len())
+                .map(|i| format_ident!("field_{}", i))
+                .collect::<Vec<_>>();
+            let mapped = fields
+                .unnamed
+                .iter()
+                .zip(&bindings)
+                .map(|(f, binding)| mapper.map(&f.ty, quote!(#binding)))
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            (quote!(( #( #bindings ),* )), quote!(( #( #mapped ),* )))
+        }
+        Fields::Unit => (quote!(), quote!()),
+    })
+}
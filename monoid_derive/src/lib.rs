@@ -1,22 +1,67 @@
 use proc_macro::TokenStream;
-use syn::{Data, DataStruct, DeriveInput, Fields, GenericParam, Generics, TypeParamBound, parse_macro_input, parse_quote};
+use syn::{Data, DataStruct, DeriveInput, Fields, parse_macro_input, parse_quote};
 use syn::spanned::Spanned;
 use quote::{quote, quote_spanned};
 
-#[proc_macro_derive(Semigroup)]
+mod attrs;
+mod bounds;
+mod enums;
+mod fields;
+mod functor;
+mod partial;
+mod patch;
+
+use fields::FieldConfig;
+
+/// Derives `Semigroup` by combining every field with the same field of the other value.
+///
+/// A field can be combined another way with one of these attributes:
+/// - `#[semigroup(with = path::to::fn)]` calls `fn(lhs, rhs)`.
+/// - `#[semigroup(first)]` keeps the left hand side and `#[semigroup(last)]` the right hand side.
+/// - `#[semigroup(skip)]` leaves the field out and uses its `Default` value.
+/// - `#[semigroup(via = Max)]` wraps both sides in `Max`, combines them and unwraps the result.
+///
+/// Every field type that uses a type parameter gets the bound it needs in the where clause, like `Sum<T>: Semigroup`.
+/// Those bounds can be replaced with `#[semigroup(bound = "T: Ord")]` on a field or on the type itself.
+///
+/// The generated code refers to `::partial_functional`, use `#[partial_functional(crate = "path::to::crate")]` if
+/// the crate can be found somewhere else.
+///
+/// Enums combine equal variants field by field. A unit variant marked with `#[semigroup(identity)]` is dropped when
+/// it is combined, a variant marked with `#[semigroup(into = Other)]` is turned into `Other` through `From` before
+/// it is combined, and `#[semigroup(mixed = "left" | "right" | "priority")]` on the enum decides what happens when
+/// any other two variants meet. Keeping the `"left"` or `"right"` side is only allowed when every combined variant is
+/// a unit variant, since it wouldn't be associative otherwise.
+#[proc_macro_derive(Semigroup, attributes(semigroup, monoid, partial_functional))]
 pub fn semigroup(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, generics, .. } = parse_macro_input!(input);
+    let input = parse_macro_input!(input);
+
+    expand_semigroup(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_semigroup(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let DeriveInput { ident, data, generics, attrs, .. } = input;
+
+    let krate = crate_path(&attrs)?;
+    let params = bounds::type_params(&generics);
+    let mixed: &[&str] = if let Data::Enum(_) = data { &["mixed"] } else { &[] };
+    let container_bound = container_bound(&attrs, "semigroup", mixed)?;
+    let mut predicates = Vec::new();
 
-    let generics = add_trait_bounds(generics, parse_quote!(Semigroup));
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let combine_fn = match data {
         Data::Struct(DataStruct{ fields: Fields::Named(ref fields), .. }) => {
             let recurse = fields.named.iter().map(|f| {
                 let name = &f.ident;
-                quote_spanned! {f.span()=>
-                    #name: self.#name.combine(rhs.#name)
-                }
-            });
+                let config = FieldConfig::parse(f)?;
+                predicates.extend(config.semigroup_bound(&krate, f, &params));
+
+                let combined = config.combine(&krate, f, quote!(self.#name), quote!(rhs.#name));
+                Ok(quote_spanned! {f.span()=>
+                    #name: #combined
+                })
+            }).collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
                 fn combine(self, rhs: Self) -> Self {
@@ -27,40 +72,80 @@ pub fn semigroup(input: TokenStream) -> TokenStream {
             }
         },
         Data::Struct(DataStruct{ fields: Fields::Unnamed(ref fields), .. }) => {
-            let i = (0..fields.unnamed.len()).map(syn::Index::from);
+            let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                let i = syn::Index::from(i);
+                let config = FieldConfig::parse(f)?;
+                predicates.extend(config.semigroup_bound(&krate, f, &params));
+
+                Ok(config.combine(&krate, f, quote!(self.#i), quote!(rhs.#i)))
+            }).collect::<syn::Result<Vec<_>>>()?;
+
             quote! {
                 fn combine(self, rhs: Self) -> Self {
-                    Self( #( self.#i.combine(rhs.#i) ),* )
+                    Self( #( #recurse ),* )
                 }
             }
         },
-        _ => unimplemented!(),
+        Data::Enum(ref data) => {
+            let (combine_fn, bounds) = enums::combine(&krate, &ident, &attrs, data, &params)?;
+            predicates.extend(bounds);
+            combine_fn
+        },
+        Data::Struct(DataStruct{ fields: Fields::Unit, .. }) => quote! {
+            fn combine(self, _rhs: Self) -> Self {
+                Self
+            }
+        },
+        Data::Union(ref data) => return Err(unsupported(data, "Semigroup")),
     };
 
-    let output = quote! {
-        impl #impl_generics Semigroup for #ident #ty_generics #where_clause {
+    let generics = bounds::with_predicates(generics, container_bound.unwrap_or(predicates));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::semigroup::Semigroup for #ident #ty_generics #where_clause {
             #combine_fn
         }
-    };
-
-    output.into()
+    })
 }
 
-#[proc_macro_derive(Monoid)]
+/// Derives `Monoid` by using the identity of every field, or the expression given by `#[monoid(empty = expr)]`.
+///
+/// A field combined with `first`, `last` or `skip` has no identity, and one combined `with` a function needs an
+/// `empty` expression that the function treats as the identity, otherwise the derive is rejected.
+///
+/// For enums the identity is the variant marked with `#[semigroup(identity)]`. The inferred bounds can be replaced
+/// with `#[monoid(bound = "...")]` like the bounds of [Semigroup](macro@Semigroup).
+#[proc_macro_derive(Monoid, attributes(semigroup, monoid, partial_functional))]
 pub fn monoid(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, generics, .. } = parse_macro_input!(input);
+    let input = parse_macro_input!(input);
 
-    let generics = add_trait_bounds(generics, parse_quote!(Monoid));
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    expand_monoid(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_monoid(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let DeriveInput { ident, data, generics, attrs, .. } = input;
+
+    let krate = crate_path(&attrs)?;
+    let params = bounds::type_params(&generics);
+    let container_bound = container_bound(&attrs, "monoid", &[])?;
+    let mut predicates = Vec::new();
 
     let monoid_fn = match data {
         Data::Struct(DataStruct{ fields: Fields::Named(ref fields), .. }) => {
             let recurse = fields.named.iter().map(|f| {
                 let name = &f.ident;
-                quote_spanned! {f.span()=>
-                    #name: Monoid::empty()
-                }
-            });
+                let config = FieldConfig::parse(f)?;
+                config.check_monoid()?;
+                predicates.extend(config.monoid_bound(&krate, f, &params));
+
+                let empty = config.empty(&krate, f);
+                Ok(quote_spanned! {f.span()=>
+                    #name: #empty
+                })
+            }).collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
                 fn empty() -> Self {
@@ -72,10 +157,12 @@ pub fn monoid(input: TokenStream) -> TokenStream {
         },
         Data::Struct(DataStruct{ fields: Fields::Unnamed(ref fields), .. }) => {
             let recurse = fields.unnamed.iter().map(|f| {
-                quote_spanned! {f.span()=>
-                    Monoid::empty()
-                }
-            });
+                let config = FieldConfig::parse(f)?;
+                config.check_monoid()?;
+                predicates.extend(config.monoid_bound(&krate, f, &params));
+
+                Ok(config.empty(&krate, f))
+            }).collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
                 fn empty() -> Self {
@@ -83,24 +170,123 @@ pub fn monoid(input: TokenStream) -> TokenStream {
                 }
             }
         },
-        _ => unimplemented!(),
+        Data::Enum(ref data) => enums::empty(&ident, data)?,
+        Data::Struct(DataStruct{ fields: Fields::Unit, .. }) => quote! {
+            fn empty() -> Self {
+                Self
+            }
+        },
+        Data::Union(ref data) => return Err(unsupported(data, "Monoid")),
     };
 
-    let output = quote! {
-        impl #impl_generics Monoid for #ident #ty_generics #where_clause {
+    // Whatever the fields need, the type has to be a Semigroup to be a Monoid.
+    let (_, ty_generics, _) = generics.split_for_impl();
+    predicates.push(parse_quote!(#ident #ty_generics: #krate::semigroup::Semigroup));
+
+    let generics = bounds::with_predicates(generics.clone(), container_bound.unwrap_or(predicates));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::monoid::Monoid for #ident #ty_generics #where_clause {
             #monoid_fn
         }
-    };
+    })
+}
+
+/// Derives `HKT` and `FunctorMut` over the last type parameter of a struct or an enum.
+///
+/// A field of that type is passed to the function, a field that is a `FunctorMut` over it, like `Vec<T>` or
+/// `Option<Vec<T>>`, is mapped with `fmap_mut` and any other field is moved into the result as it is. Since the
+/// function can be called for any number of fields, there is no derive for `Functor` and its `FnOnce`. The
+/// crate can be changed with `#[partial_functional(crate = "...")]` like for [Semigroup](macro@Semigroup).
+#[proc_macro_derive(FunctorMut, attributes(partial_functional))]
+pub fn functor(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    crate_path(&input.attrs)
+        .and_then(|krate| functor::expand(&krate, input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derives `Partial` for a struct with named fields, along with a `PartialName` struct where every field can be left
+/// out. The partial struct is a `Monoid` where a field that is set on the right hand side replaces the left hand side,
+/// and its `build()` gives back the whole value or every field that is missing, like `server.port`.
+///
+/// These attributes change how a field is handled:
+/// - A field that is an `Option` already can be left out and becomes `None`.
+/// - `#[partial(default)]` or `#[partial(default = expr)]` falls back to `Default::default()` or `expr`.
+/// - `#[partial(nested)]` uses the partial type of a field that is `Partial` itself, so its own fields are layered.
+/// - `#[partial(attr(...))]` puts `#[...]` on the field of the partial struct.
+///
+/// On the struct, `#[partial(derive(Debug, Clone))]` derives traits for the partial struct and
+/// `#[partial(attr(...))]` puts `#[...]` on it.
+#[proc_macro_derive(Partial, attributes(partial, partial_functional))]
+pub fn partial(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    crate_path(&input.attrs)
+        .and_then(|krate| partial::expand(&krate, input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derives a `NamePatch` for a struct with named fields, where every field is an `Option<Last<_>>` that sets the same
+/// field of the struct when it holds a value.
+///
+/// The patch is a `Monoid` where a later patch replaces the fields it sets, it has `apply(&self, &mut Name)` and it
+/// is a `MonoidAction` on the struct, so patches can be combined before they are applied. `NamePatch::diff(&old,
+/// &new)` gives the patch that turns `old` into `new`.
+///
+/// A field marked with `#[patch(skip)]` can't be patched, `#[patch(attr(...))]` puts `#[...]` on a field of the patch
+/// and on the struct `#[patch(derive(Debug, Clone))]` and `#[patch(attr(...))]` go on the patch itself. When the
+/// skipped fields are the only ones using a generic parameter the patch gets a hidden `__marker: PhantomData` for it.
+#[proc_macro_derive(Patch, attributes(patch, partial_functional))]
+pub fn patch(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    crate_path(&input.attrs)
+        .and_then(|krate| patch::expand(&krate, input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// The path to the partial_functional crate in the generated code, which can be changed with
+/// `#[partial_functional(crate = "path::to::crate")]` when the dependency is renamed or re-exported.
+fn crate_path(attrs: &[syn::Attribute]) -> syn::Result<syn::Path> {
+    let mut krate = parse_quote!(::partial_functional);
+    for arg in attrs::args(attrs, "partial_functional")? {
+        if !arg.is("crate") {
+            return Err(arg.unknown("partial_functional", &["crate"]));
+        }
+        krate = arg.str()?.parse()?;
+    }
 
-    output.into()
+    Ok(krate)
 }
 
-fn add_trait_bounds(mut generics: Generics, ty: TypeParamBound) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param.bounds.push(ty.clone());
+/// The bounds given on the type itself with `#[namespace(bound = "...")]`. Any argument in `allowed` is left for
+/// someone else to check.
+fn container_bound(attrs: &[syn::Attribute], namespace: &str, allowed: &[&str]) -> syn::Result<Option<Vec<syn::WherePredicate>>> {
+    let mut bound = None;
+    for arg in attrs::args(attrs, namespace)? {
+        if arg.is("bound") {
+            bound = Some(bounds::parse(&arg)?);
+        } else if !allowed.iter().any(|name| arg.is(name)) {
+            let expected = allowed.iter().copied().chain(Some("bound")).collect::<Vec<_>>();
+            return Err(arg.unknown(namespace, &expected));
         }
     }
 
-    generics
+    Ok(bound)
+}
+
+fn unsupported(data: &syn::DataUnion, derive: &str) -> syn::Error {
+    syn::Error::new(
+        data.union_token.span(),
+        format!(
+            "{} can't be derived for a union, only for structs and for enums with #[semigroup] attributes",
+            derive
+        ),
+    )
 }
@@ -0,0 +1,308 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DataStruct, DeriveInput, Expr, Field, Fields, GenericArgument, Ident, Path, PathArguments, Type,
+    WherePredicate,
+};
+
+use crate::attrs::{self, error};
+use crate::bounds;
+
+/// How a field of the partial type is turned into the field of the whole value.
+enum Kind {
+    /// The field has to be set.
+    Required,
+    /// The field is an `Option` already, leaving it out gives `None`.
+    Optional,
+    /// The field falls back to `Default::default()` or the given expression.
+    Default(Option<Box<Expr>>),
+    /// The field is a `Partial` itself and is built from its own partial type.
+    Nested,
+}
+
+struct FieldConfig<'a> {
+    field: &'a Field,
+    name: &'a Ident,
+    kind: Kind,
+    attrs: Vec<TokenStream>,
+}
+
+impl<'a> FieldConfig<'a> {
+    fn parse(field: &'a Field) -> syn::Result<Self> {
+        let mut kind = if is_option(&field.ty) {
+            Kind::Optional
+        } else {
+            Kind::Required
+        };
+        let mut attrs = Vec::new();
+        let mut seen = None;
+
+        for arg in attrs::args(&field.attrs, "partial")? {
+            if arg.is("attr") {
+                attrs.push(arg.list()?.clone());
+                continue;
+            }
+
+            if let Some(seen) = seen.replace(arg.name.clone()) {
+                return error(
+                    arg.name.span(),
+                    format!(
+                        "`{}` can't be used together with `{}` on the same field",
+                        arg.name, seen
+                    ),
+                );
+            }
+
+            if arg.is("nested") {
+                arg.flag()?;
+                kind = Kind::Nested;
+            } else if arg.is("default") {
+                kind = Kind::Default(match arg.value {
+                    Some(_) => Some(Box::new(arg.expr()?.clone())),
+                    None => {
+                        arg.flag()?;
+                        None
+                    }
+                });
+            } else {
+                return Err(arg.unknown("partial", &["nested", "default", "attr"]));
+            }
+        }
+
+        Ok(Self {
+            field,
+            name: field.ident.as_ref().unwrap(),
+            kind,
+            attrs,
+        })
+    }
+
+    /// The type of the field in the partial type.
+    fn partial_ty(&self, krate: &Path) -> Type {
+        let ty = &self.field.ty;
+        match self.kind {
+            Kind::Optional => ty.clone(),
+            Kind::Nested => parse_quote!(<#ty as #krate::partial::Partial>::Partial),
+            Kind::Required | Kind::Default(_) => parse_quote!(::core::option::Option<#ty>),
+        }
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else { return false };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+
+    segment.ident == "Option"
+        && matches!(&segment.arguments, PathArguments::AngleBracketed(args)
+            if args.args.len() == 1 && matches!(args.args[0], GenericArgument::Type(_)))
+}
+
+/// Derives `Partial` for a struct with named fields, along with its partial type `PartialName`.
+pub(crate) fn expand(krate: &Path, input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        vis,
+    } = input;
+
+    let fields = match &data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        Data::Struct(DataStruct { struct_token, .. }) => {
+            return error(
+                struct_token.span(),
+                "Partial can only be derived for a struct with named fields",
+            )
+        }
+        Data::Enum(data) => {
+            return error(
+                data.enum_token.span(),
+                "Partial can only be derived for a struct with named fields",
+            )
+        }
+        Data::Union(data) => {
+            return error(
+                data.union_token.span(),
+                "Partial can only be derived for a struct with named fields",
+            )
+        }
+    };
+
+    let mut derives = Vec::new();
+    let mut container_attrs = Vec::new();
+    for arg in attrs::args(&attrs, "partial")? {
+        if arg.is("derive") {
+            derives.push(arg.list()?.clone());
+        } else if arg.is("attr") {
+            container_attrs.push(arg.list()?.clone());
+        } else {
+            return Err(arg.unknown("partial", &["derive", "attr"]));
+        }
+    }
+
+    let configs = fields
+        .named
+        .iter()
+        .map(FieldConfig::parse)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // A nested field that uses a type parameter has to be `Partial` for its partial type to exist.
+    let params = bounds::type_params(&generics);
+    let predicates = configs
+        .iter()
+        .filter(|config| matches!(config.kind, Kind::Nested) && bounds::mentions(&config.field.ty, &params))
+        .map(|config| {
+            let ty = &config.field.ty;
+            parse_quote!(#ty: #krate::partial::Partial)
+        })
+        .collect::<Vec<WherePredicate>>();
+    let generics = bounds::with_predicates(generics, predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let partial = format_ident!("Partial{}", ident);
+    let doc = format!(
+        " A [`{}`] where every field can be left out, see [`{}::build`].",
+        ident, partial
+    );
+
+    let names = configs.iter().map(|config| config.name).collect::<Vec<_>>();
+    let declarations = configs.iter().map(|config| {
+        let FieldConfig { field, name, attrs, .. } = config;
+        let vis = &field.vis;
+        let ty = config.partial_ty(krate);
+        let docs = field.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+        quote_spanned! {field.span()=>
+            #( #docs )*
+            #( #[#attrs] )*
+            #vis #name: #ty
+        }
+    });
+
+    let combined = configs.iter().map(|config| {
+        let name = config.name;
+        match config.kind {
+            Kind::Nested => quote!(#krate::semigroup::Semigroup::combine(self.#name, rhs.#name)),
+            _ => quote!(rhs.#name.or(self.#name)),
+        }
+    });
+
+    let empty = configs.iter().map(|config| match config.kind {
+        Kind::Nested => quote!(#krate::monoid::Monoid::empty()),
+        _ => quote!(::core::option::Option::None),
+    });
+
+    let from_whole = configs.iter().map(|config| {
+        let name = config.name;
+        match config.kind {
+            Kind::Optional => quote!(value.#name),
+            Kind::Nested => quote!(::core::convert::From::from(value.#name)),
+            Kind::Required | Kind::Default(_) => quote!(::core::option::Option::Some(value.#name)),
+        }
+    });
+
+    // Every field is looked at before giving up, so that all of the missing fields are reported at once. The fields
+    // are bound to names with a prefix, so that none of them can shadow `missing`.
+    let bindings = names
+        .iter()
+        .map(|name| format_ident!("field_{}", name.unraw()))
+        .collect::<Vec<_>>();
+    let built = configs.iter().zip(&bindings).map(|(config, binding)| {
+        let name = config.name;
+        let path = name.unraw().to_string();
+        let value = match &config.kind {
+            Kind::Required => quote! {
+                match partial.#name {
+                    ::core::option::Option::Some(value) => ::core::option::Option::Some(value),
+                    ::core::option::Option::None => {
+                        missing.push(#path);
+                        ::core::option::Option::None
+                    }
+                }
+            },
+            Kind::Optional => quote!(::core::option::Option::Some(partial.#name)),
+            Kind::Default(None) => quote! {
+                ::core::option::Option::Some(partial.#name.unwrap_or_default())
+            },
+            Kind::Default(Some(expr)) => quote! {
+                ::core::option::Option::Some(partial.#name.unwrap_or_else(|| #expr))
+            },
+            Kind::Nested => quote! {
+                match #krate::partial::Partial::from_partial(partial.#name) {
+                    ::core::result::Result::Ok(value) => ::core::option::Option::Some(value),
+                    ::core::result::Result::Err(nested) => {
+                        missing.nested(#path, nested);
+                        ::core::option::Option::None
+                    }
+                }
+            },
+        };
+
+        quote!(let #binding = #value;)
+    });
+
+    Ok(quote! {
+        #[doc = #doc]
+        #( #[derive(#derives)] )*
+        #( #[#container_attrs] )*
+        #vis struct #partial #generics #where_clause {
+            #( #declarations ),*
+        }
+
+        impl #impl_generics #partial #ty_generics #where_clause {
+            /// Builds the whole value, or gives back every field that is missing.
+            #vis fn build(self) -> ::core::result::Result<#ident #ty_generics, #krate::partial::MissingFields> {
+                <#ident #ty_generics as #krate::partial::Partial>::from_partial(self)
+            }
+        }
+
+        impl #impl_generics ::core::default::Default for #partial #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #( #names: #empty ),*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::semigroup::Semigroup for #partial #ty_generics #where_clause {
+            fn combine(self, rhs: Self) -> Self {
+                Self {
+                    #( #names: #combined ),*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #partial #ty_generics #where_clause {
+            fn from(value: #ident #ty_generics) -> Self {
+                Self {
+                    #( #names: #from_whole ),*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::partial::Partial for #ident #ty_generics #where_clause {
+            type Partial = #partial #ty_generics;
+
+            fn from_partial(partial: Self::Partial) -> ::core::result::Result<Self, #krate::partial::MissingFields> {
+                #[allow(unused_mut)]
+                let mut missing = #krate::partial::MissingFields::new();
+                #( #built )*
+
+                match (#( #bindings, )*) {
+                    (#( ::core::option::Option::Some(#bindings), )*) => {
+                        ::core::result::Result::Ok(Self { #( #names: #bindings ),* })
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::result::Result::Err(missing),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,261 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Field, Fields, Generics, Lifetime, Path, Type, WherePredicate};
+
+use crate::attrs::{self, error};
+use crate::bounds;
+
+struct FieldConfig<'a> {
+    field: &'a Field,
+    skip: bool,
+    attrs: Vec<TokenStream>,
+}
+
+impl<'a> FieldConfig<'a> {
+    fn parse(field: &'a Field) -> syn::Result<Self> {
+        let mut config = Self {
+            field,
+            skip: false,
+            attrs: Vec::new(),
+        };
+
+        for arg in attrs::args(&field.attrs, "patch")? {
+            if arg.is("skip") {
+                arg.flag()?;
+                config.skip = true;
+            } else if arg.is("attr") {
+                config.attrs.push(arg.list()?.clone());
+            } else {
+                return Err(arg.unknown("patch", &["skip", "attr"]));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Derives a `NamePatch` for a struct with named fields, which sets any of its fields with `Option<Last<_>>`.
+pub(crate) fn expand(krate: &Path, input: DeriveInput) -> syn::Result<TokenStream> {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        vis,
+    } = input;
+
+    let fields = match &data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        Data::Struct(DataStruct { struct_token, .. }) => {
+            return error(
+                struct_token.span(),
+                "Patch can only be derived for a struct with named fields",
+            )
+        }
+        Data::Enum(data) => {
+            return error(
+                data.enum_token.span(),
+                "Patch can only be derived for a struct with named fields",
+            )
+        }
+        Data::Union(data) => {
+            return error(
+                data.union_token.span(),
+                "Patch can only be derived for a struct with named fields",
+            )
+        }
+    };
+
+    let mut derives = Vec::new();
+    let mut container_attrs = Vec::new();
+    for arg in attrs::args(&attrs, "patch")? {
+        if arg.is("derive") {
+            derives.push(arg.list()?.clone());
+        } else if arg.is("attr") {
+            container_attrs.push(arg.list()?.clone());
+        } else {
+            return Err(arg.unknown("patch", &["derive", "attr"]));
+        }
+    }
+
+    let configs = fields
+        .named
+        .iter()
+        .map(FieldConfig::parse)
+        .collect::<syn::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|config| !config.skip)
+        .collect::<Vec<_>>();
+
+    // Applying a patch clones its values and a diff compares the fields, so only those need the bounds.
+    let params = bounds::type_params(&generics);
+    let field_types = configs
+        .iter()
+        .map(|config| &config.field.ty)
+        .filter(|ty| bounds::mentions(ty, &params))
+        .collect::<Vec<_>>();
+    let apply_bounds = field_types
+        .iter()
+        .map(|ty| parse_quote!(#ty: ::core::clone::Clone))
+        .collect::<Vec<WherePredicate>>();
+    let diff_bounds = field_types
+        .iter()
+        .map(|ty| parse_quote!(#ty: ::core::clone::Clone + ::core::cmp::PartialEq))
+        .collect::<Vec<WherePredicate>>();
+
+    let apply_generics = bounds::with_predicates(generics.clone(), apply_bounds);
+    let diff_generics = bounds::with_predicates(generics.clone(), diff_bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (apply_impl_generics, _, apply_where_clause) = apply_generics.split_for_impl();
+    let (diff_impl_generics, _, diff_where_clause) = diff_generics.split_for_impl();
+
+    let patch = format_ident!("{}Patch", ident);
+    let doc = format!(
+        " Sets any of the fields of a [`{}`], a patch that is combined later replaces the fields it sets.",
+        ident
+    );
+
+    let names = configs
+        .iter()
+        .map(|config| config.field.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let declarations = configs.iter().map(|config| {
+        let FieldConfig { field, attrs, .. } = config;
+        let Field { ident, vis, ty, .. } = field;
+        let docs = field.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+        quote_spanned! {field.span()=>
+            #( #docs )*
+            #( #[#attrs] )*
+            #vis #ident: ::core::option::Option<#krate::monoid::Last<#ty>>
+        }
+    });
+
+    // The parameters that only skipped fields use are still declared by the patch, so a marker has to use them.
+    let unused = unused_params(&generics, &configs);
+    let marker = unused.as_ref().map(|unused| {
+        quote! {
+            #[doc(hidden)]
+            #vis __marker: ::core::marker::PhantomData<fn() -> #unused>
+        }
+    });
+    let markers = unused
+        .iter()
+        .map(|_| quote!(__marker: ::core::marker::PhantomData))
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        #[doc = #doc]
+        #( #[derive(#derives)] )*
+        #( #[#container_attrs] )*
+        #vis struct #patch #generics #where_clause {
+            #( #declarations, )*
+            #marker
+        }
+
+        impl #apply_impl_generics #patch #ty_generics #apply_where_clause {
+            /// Sets every field of `target` that this patch sets.
+            #vis fn apply(&self, target: &mut #ident #ty_generics) {
+                #(
+                    if let ::core::option::Option::Some(#krate::monoid::Last(::core::option::Option::Some(value))) =
+                        &self.#names
+                    {
+                        target.#names = ::core::clone::Clone::clone(value);
+                    }
+                )*
+            }
+        }
+
+        impl #diff_impl_generics #patch #ty_generics #diff_where_clause {
+            /// The patch that sets every field that is different in `new`, so applying it to `old` gives `new`.
+            #vis fn diff(old: &#ident #ty_generics, new: &#ident #ty_generics) -> Self {
+                Self {
+                    #( #markers, )*
+                    #(
+                        #names: if old.#names == new.#names {
+                            ::core::option::Option::None
+                        } else {
+                            ::core::option::Option::Some(#krate::monoid::Last::from(
+                                ::core::clone::Clone::clone(&new.#names),
+                            ))
+                        }
+                    ),*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::default::Default for #patch #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #( #markers, )*
+                    #( #names: ::core::option::Option::None ),*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::semigroup::Semigroup for #patch #ty_generics #where_clause {
+            fn combine(self, rhs: Self) -> Self {
+                Self {
+                    #( #markers, )*
+                    #( #names: #krate::semigroup::Semigroup::combine(self.#names, rhs.#names) ),*
+                }
+            }
+        }
+
+        impl #apply_impl_generics #krate::action::MonoidAction<#ident #ty_generics> for #patch #ty_generics
+            #apply_where_clause
+        {
+            fn act(&self, mut target: #ident #ty_generics) -> #ident #ty_generics {
+                self.apply(&mut target);
+                target
+            }
+        }
+    })
+}
+
+/// A tuple of the lifetimes and type parameters that none of the patched fields use, if there are any.
+fn unused_params(generics: &Generics, configs: &[FieldConfig]) -> Option<Type> {
+    let types = configs.iter().map(|config| &config.field.ty).collect::<Vec<_>>();
+
+    let lifetimes = generics
+        .lifetimes()
+        .map(|param| &param.lifetime)
+        .filter(|lifetime| !types.iter().any(|ty| mentions_lifetime(ty, lifetime)))
+        .map(|lifetime| quote!(&#lifetime ()));
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .filter(|ident| !types.iter().any(|ty| bounds::mentions(ty, std::slice::from_ref(ident))))
+        .map(|ident| quote!(#ident));
+
+    let unused = lifetimes.chain(params).collect::<Vec<_>>();
+    if unused.is_empty() {
+        None
+    } else {
+        Some(parse_quote!(( #( #unused, )* )))
+    }
+}
+
+fn mentions_lifetime(ty: &Type, lifetime: &Lifetime) -> bool {
+    fn walk(tokens: TokenStream, lifetime: &Lifetime) -> bool {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                    if matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if *ident == lifetime.ident) {
+                        return true;
+                    }
+                }
+                TokenTree::Group(group) if walk(group.stream(), lifetime) => return true,
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    walk(ty.to_token_stream(), lifetime)
+}
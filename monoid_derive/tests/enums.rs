@@ -0,0 +1,169 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::prelude::*;
+use quickcheck::{Arbitrary, Gen};
+use quickcheck_macros::quickcheck;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    quantity: Sum<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup)]
+struct Total {
+    quantity: Sum<u32>,
+    lines: Sum<u32>,
+}
+
+impl From<Line> for Total {
+    fn from(line: Line) -> Self {
+        Self {
+            quantity: line.quantity,
+            lines: Sum(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup, Monoid)]
+enum Order {
+    #[semigroup(into = Total)]
+    Line(Line),
+    Total(Total),
+    #[semigroup(identity)]
+    Empty,
+}
+
+fn line(quantity: u32) -> Order {
+    Order::Line(Line {
+        quantity: Sum(quantity),
+    })
+}
+
+fn total(quantity: u32, lines: u32) -> Order {
+    Order::Total(Total {
+        quantity: Sum(quantity),
+        lines: Sum(lines),
+    })
+}
+
+#[test]
+fn identity_is_empty() {
+    assert_eq!(Order::Empty, Order::empty());
+    assert_eq!(line(3), Order::empty().combine(line(3)));
+    assert_eq!(line(3), line(3).combine(Order::empty()));
+}
+
+#[test]
+fn converted_variants_combine_as_the_target() {
+    assert_eq!(total(5, 2), line(2).combine(line(3)));
+    assert_eq!(total(9, 4), total(4, 2).combine(line(2)).combine(line(3)));
+}
+
+#[test]
+fn fold_an_order() {
+    let order = [line(1), Order::Empty, line(2), total(3, 2)]
+        .into_iter()
+        .fold(Order::empty(), Semigroup::combine);
+
+    assert_eq!(total(6, 4), order);
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup)]
+#[semigroup(mixed = "priority")]
+enum Status {
+    Failed { errors: Vec<String> },
+    Warning(Vec<String>, Sum<u32>),
+    Ok,
+}
+
+#[test]
+fn same_variants_combine_field_wise() {
+    let a = Status::Warning(vec![String::from("a")], Sum(1));
+    let b = Status::Warning(vec![String::from("b")], Sum(1));
+
+    assert_eq!(Status::Warning(vec![String::from("a"), String::from("b")], Sum(2)), a.combine(b));
+    assert_eq!(Status::Ok, Status::Ok.combine(Status::Ok));
+
+    let failed = |error: &str| Status::Failed {
+        errors: vec![error.to_owned()],
+    };
+    assert_eq!(
+        Status::Failed {
+            errors: vec![String::from("x"), String::from("y")]
+        },
+        failed("x").combine(failed("y"))
+    );
+}
+
+#[test]
+fn priority_keeps_the_variant_declared_first() {
+    let warning = || Status::Warning(vec![String::from("w")], Sum(1));
+    let failed = || Status::Failed { errors: vec![] };
+
+    assert_eq!(failed(), warning().combine(failed()));
+    assert_eq!(failed(), failed().combine(Status::Ok));
+    assert_eq!(warning(), Status::Ok.combine(warning()));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Semigroup, Monoid)]
+#[semigroup(mixed = "left")]
+enum Keep {
+    A,
+    B,
+    #[semigroup(identity)]
+    Neither,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Semigroup)]
+#[semigroup(mixed = "right")]
+enum KeepRight {
+    A,
+    B,
+}
+
+#[test]
+fn left_and_right_keep_one_side() {
+    assert_eq!(Keep::A, Keep::A.combine(Keep::B));
+    assert_eq!(Keep::B, Keep::Neither.combine(Keep::B));
+    assert_eq!(Keep::Neither, Keep::empty());
+
+    assert_eq!(KeepRight::B, KeepRight::A.combine(KeepRight::B));
+}
+
+impl Arbitrary for Keep {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[Keep::A, Keep::B, Keep::Neither]).unwrap()
+    }
+}
+
+impl Arbitrary for KeepRight {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[KeepRight::A, KeepRight::B]).unwrap()
+    }
+}
+
+impl Arbitrary for Status {
+    fn arbitrary(g: &mut Gen) -> Self {
+        match u8::arbitrary(g) % 3 {
+            0 => Status::Failed {
+                errors: Vec::arbitrary(g),
+            },
+            1 => Status::Warning(Vec::arbitrary(g), Sum(u8::arbitrary(g) as u32)),
+            _ => Status::Ok,
+        }
+    }
+}
+
+#[quickcheck]
+fn left_is_associative(x: Keep, y: Keep, z: Keep) -> bool {
+    x.combine(y.combine(z)) == x.combine(y).combine(z)
+}
+
+#[quickcheck]
+fn right_is_associative(x: KeepRight, y: KeepRight, z: KeepRight) -> bool {
+    x.combine(y.combine(z)) == x.combine(y).combine(z)
+}
+
+#[quickcheck]
+fn priority_is_associative(x: Status, y: Status, z: Status) -> bool {
+    x.clone().combine(y.clone().combine(z.clone())) == x.combine(y).combine(z)
+}
@@ -0,0 +1,133 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::prelude::*;
+use quickcheck_macros::quickcheck;
+
+fn longest(lhs: String, rhs: String) -> String {
+    if rhs.len() > lhs.len() {
+        rhs
+    } else {
+        lhs
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup)]
+struct ProductLine {
+    #[semigroup(first)]
+    code: String,
+    #[semigroup(last)]
+    price: f32,
+    quantity: Sum<u32>,
+    #[semigroup(via = Max)]
+    largest_quantity: u32,
+    #[semigroup(with = longest)]
+    description: String,
+    #[semigroup(skip)]
+    cache: Option<u32>,
+}
+
+fn line(code: &str, price: f32, quantity: u32, description: &str) -> ProductLine {
+    ProductLine {
+        code: code.to_owned(),
+        price,
+        quantity: Sum(quantity),
+        largest_quantity: quantity,
+        description: description.to_owned(),
+        cache: Some(quantity),
+    }
+}
+
+#[test]
+fn every_field_uses_its_own_strategy() {
+    let combined = line("AAA", 1.5, 2, "apples").combine(line("BBB", 2.5, 5, "pears, ripe"));
+
+    let expected = ProductLine {
+        code: String::from("AAA"),
+        price: 2.5,
+        quantity: Sum(7),
+        largest_quantity: 5,
+        description: String::from("pears, ripe"),
+        cache: None,
+    };
+    assert_eq!(expected, combined);
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup, Monoid)]
+struct Stock {
+    quantity: Sum<u32>,
+    #[semigroup(via = Max)]
+    #[monoid(empty = 0)]
+    largest: u32,
+    #[semigroup(with = longest)]
+    #[monoid(empty = String::new())]
+    description: String,
+}
+
+fn stock(quantity: u32, description: &str) -> Stock {
+    Stock {
+        quantity: Sum(quantity),
+        largest: quantity,
+        description: description.to_owned(),
+    }
+}
+
+#[test]
+fn empty_uses_the_given_expressions() {
+    let empty = Stock::empty();
+
+    assert_eq!(Sum(0), empty.quantity);
+    assert_eq!(0, empty.largest);
+    assert_eq!(String::new(), empty.description);
+}
+
+#[quickcheck]
+fn identity_property(quantity: u32, description: String) -> bool {
+    let x = stock(quantity, &description);
+
+    Stock::empty().combine(x.clone()) == x && x.clone().combine(Stock::empty()) == x
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup, Monoid)]
+struct Peak(
+    #[semigroup(via = Max)] u64,
+    #[semigroup(via = Min)] u64,
+    Last<&'static str>,
+);
+
+#[test]
+fn tuple_structs_and_via_without_an_empty() {
+    let peak = [
+        Peak(3, 3, Last::from("a")),
+        Peak(9, 9, Last::from("b")),
+        Peak(1, 1, Last(None)),
+    ]
+    .into_iter()
+    .fold(Peak::empty(), Semigroup::combine);
+
+    assert_eq!(Peak(9, 1, Last::from("b")), peak);
+    assert_eq!(Peak(0, u64::MAX, Last(None)), Peak::empty());
+}
+
+#[quickcheck]
+fn via_identity_property(max: u64, min: u64) -> bool {
+    let x = Peak(max, min, Last(None));
+
+    Peak::empty().combine(x.clone()) == x && x.clone().combine(Peak::empty()) == x
+}
+
+#[derive(Debug, Clone, PartialEq, Semigroup)]
+enum Reading {
+    Sensor {
+        #[semigroup(first)]
+        id: u32,
+        #[semigroup(via = Max)]
+        highest: i32,
+    },
+}
+
+#[test]
+fn enum_fields_use_their_strategy() {
+    let a = Reading::Sensor { id: 1, highest: 20 };
+    let b = Reading::Sensor { id: 2, highest: 25 };
+
+    assert_eq!(Reading::Sensor { id: 1, highest: 25 }, a.combine(b));
+}
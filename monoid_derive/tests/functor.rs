@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use partfun_derive::FunctorMut;
+use partial_functional::prelude::*;
+
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Tagged<T> {
+    tag: String,
+    value: T,
+}
+
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Pair<T>(T, T);
+
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Nested<T> {
+    values: Vec<T>,
+    maybe: Option<T>,
+    grouped: Vec<Option<T>>,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq, FunctorMut)]
+enum Response<E, T> {
+    Loading,
+    Failed(E),
+    Loaded { value: T, retries: Vec<T> },
+}
+
+// Bounds on the mapped parameter have to hold for the target as well.
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Labelled<'a, L, T: Debug>
+where
+    T: PartialEq,
+{
+    label: &'a L,
+    items: Vec<T>,
+    marker: PhantomData<T>,
+}
+
+// The target parameter is named so that it doesn't clash with one of the type.
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Clash<B, T> {
+    other: B,
+    value: Result<T, B>,
+}
+
+#[derive(Debug, PartialEq, FunctorMut)]
+struct Empty<T>(PhantomData<T>);
+
+#[test]
+fn maps_fields_of_the_parameter() {
+    let tagged = Tagged {
+        tag: "id".to_owned(),
+        value: 42,
+    };
+    let pair = Pair(1, 2);
+
+    assert_eq!(
+        tagged.fmap_mut(|x| x.to_string()),
+        Tagged {
+            tag: "id".to_owned(),
+            value: "42".to_owned()
+        }
+    );
+    assert_eq!(pair.fmap_mut(|x| x * 10), Pair(10, 20));
+}
+
+#[test]
+fn maps_through_nested_functors() {
+    let nested = Nested {
+        values: vec![1, 2],
+        maybe: Some(3),
+        grouped: vec![Some(4), None, Some(5)],
+        count: 7,
+    };
+
+    assert_eq!(
+        nested.fmap_mut(|x| x * 2),
+        Nested {
+            values: vec![2, 4],
+            maybe: Some(6),
+            grouped: vec![Some(8), None, Some(10)],
+            count: 7,
+        }
+    );
+}
+
+#[test]
+fn calls_the_function_in_field_order() {
+    let mut seen = Vec::new();
+    Nested {
+        values: vec![1, 2],
+        maybe: Some(3),
+        grouped: vec![Some(4)],
+        count: 0,
+    }
+    .fmap_mut(|x| seen.push(x));
+
+    assert_eq!(seen, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn maps_every_variant_over_the_last_parameter() {
+    let loaded: Response<String, u8> = Response::Loaded {
+        value: 1,
+        retries: vec![2, 3],
+    };
+    let failed: Response<String, u8> = Response::Failed("timeout".to_owned());
+    let loading: Response<String, u8> = Response::Loading;
+
+    assert_eq!(
+        loaded.fmap_mut(u32::from),
+        Response::Loaded {
+            value: 1u32,
+            retries: vec![2, 3]
+        }
+    );
+    assert_eq!(
+        failed.fmap_mut(u32::from),
+        Response::Failed("timeout".to_owned())
+    );
+    assert_eq!(loading.fmap_mut(u32::from), Response::Loading);
+}
+
+#[test]
+fn keeps_lifetimes_bounds_and_other_parameters() {
+    let label = HashMap::from([("a", 1)]);
+    let labelled = Labelled {
+        label: &label,
+        items: vec![1, 2],
+        marker: PhantomData,
+    };
+
+    let mapped = labelled.fmap_mut(|x| x > 1);
+    assert_eq!(mapped.items, vec![false, true]);
+    assert_eq!(mapped.label, &label);
+
+    let clash: Clash<&str, u8> = Clash {
+        other: "b",
+        value: Ok(1),
+    };
+    assert_eq!(
+        clash.fmap_mut(|x| x + 1),
+        Clash {
+            other: "b",
+            value: Ok(2)
+        }
+    );
+
+    assert_eq!(
+        Empty::<u8>(PhantomData).fmap_mut(|x| x as char),
+        Empty(PhantomData)
+    );
+}
+
+#[test]
+fn identity_law() {
+    let nested = || Nested {
+        values: vec![1, 2],
+        maybe: None,
+        grouped: vec![None, Some(3)],
+        count: 1,
+    };
+
+    assert_eq!(nested().fmap_mut(std::convert::identity), nested());
+}
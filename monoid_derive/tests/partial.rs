@@ -0,0 +1,159 @@
+use partfun_derive::Partial;
+use partial_functional::partial::MissingFields;
+use partial_functional::prelude::*;
+
+#[derive(Debug, PartialEq, Partial)]
+#[partial(derive(Debug, Clone, PartialEq))]
+struct Config {
+    name: String,
+    #[partial(default = 8)]
+    workers: u8,
+    #[partial(default)]
+    verbose: bool,
+    motd: Option<String>,
+    #[partial(nested)]
+    server: Server,
+}
+
+#[derive(Debug, PartialEq, Partial)]
+#[partial(derive(Debug, Clone, PartialEq))]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+fn server(host: Option<&str>, port: Option<u16>) -> PartialServer {
+    PartialServer {
+        host: host.map(str::to_owned),
+        port,
+    }
+}
+
+#[test]
+fn later_layers_replace_the_fields_they_set() {
+    let defaults = PartialConfig {
+        name: Some("app".to_owned()),
+        workers: None,
+        verbose: Some(false),
+        motd: Some("hello".to_owned()),
+        server: server(Some("localhost"), Some(80)),
+    };
+    let file = PartialConfig {
+        workers: Some(4),
+        server: server(None, Some(8080)),
+        ..PartialConfig::empty()
+    };
+    let args = PartialConfig {
+        verbose: Some(true),
+        ..PartialConfig::empty()
+    };
+
+    let config = defaults.combine(file).combine(args).build();
+
+    assert_eq!(
+        config,
+        Ok(Config {
+            name: "app".to_owned(),
+            workers: 4,
+            verbose: true,
+            motd: Some("hello".to_owned()),
+            server: Server {
+                host: "localhost".to_owned(),
+                port: 8080,
+            },
+        })
+    );
+}
+
+#[test]
+fn defaults_and_options_fill_in_what_is_left_out() {
+    let partial = PartialConfig {
+        name: Some("app".to_owned()),
+        server: server(Some("example.org"), Some(443)),
+        ..PartialConfig::default()
+    };
+
+    let config = partial.build().unwrap();
+
+    assert_eq!(8, config.workers);
+    assert!(!config.verbose);
+    assert_eq!(None, config.motd);
+}
+
+#[test]
+fn every_missing_field_is_reported() {
+    let partial = PartialConfig {
+        server: server(None, Some(443)),
+        ..PartialConfig::empty()
+    };
+
+    let missing = partial.build().unwrap_err();
+
+    assert_eq!(missing.paths(), ["name", "server.host"]);
+    assert_eq!("missing fields `name`, `server.host`", missing.to_string());
+}
+
+#[test]
+fn whole_values_convert_into_layers_where_everything_is_set() {
+    let server = Server {
+        host: "localhost".to_owned(),
+        port: 80,
+    };
+
+    let partial = PartialServer::from(server);
+    assert_eq!(partial, self::server(Some("localhost"), Some(80)));
+
+    let replaced = partial.combine(self::server(Some("example.org"), None));
+    assert_eq!(
+        replaced.build(),
+        Ok(Server {
+            host: "example.org".to_owned(),
+            port: 80,
+        })
+    );
+}
+
+#[test]
+fn combine_is_associative() {
+    let layers = [
+        server(Some("a"), None),
+        server(None, Some(1)),
+        server(Some("c"), Some(3)),
+    ];
+    let [a, b, c] = layers.clone();
+    let [x, y, z] = layers;
+
+    assert_eq!(a.combine(b).combine(c), x.combine(y.combine(z)));
+}
+
+// The fields can have any name, and the partial struct can take more attributes.
+#[derive(Debug, Partial)]
+#[partial(attr(derive(Debug)))]
+struct Awkward<T> {
+    #[partial(attr(allow(dead_code)))]
+    missing: T,
+    partial: Option<T>,
+    #[partial(default = Vec::new())]
+    r#type: Vec<T>,
+}
+
+#[test]
+fn field_names_and_generics_dont_get_in_the_way() {
+    let awkward = PartialAwkward::<u8> {
+        missing: None,
+        partial: None,
+        r#type: None,
+    };
+
+    let missing: MissingFields = awkward.build().unwrap_err();
+    assert_eq!(missing.paths(), ["missing"]);
+
+    let built = PartialAwkward {
+        missing: Some(1u8),
+        partial: None,
+        r#type: Some(vec![2]),
+    }
+    .build()
+    .unwrap();
+    assert_eq!(built.r#type, vec![2]);
+}
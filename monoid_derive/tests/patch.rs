@@ -0,0 +1,160 @@
+use partfun_derive::Patch;
+use partial_functional::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Patch)]
+#[patch(derive(Debug, Clone, PartialEq))]
+struct User {
+    #[patch(skip)]
+    id: u32,
+    name: String,
+    email: Option<String>,
+    age: u8,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "ada".to_owned(),
+        email: None,
+        age: 36,
+    }
+}
+
+fn set_name(name: &str) -> UserPatch {
+    UserPatch {
+        name: Some(Last::from(name.to_owned())),
+        ..UserPatch::empty()
+    }
+}
+
+#[test]
+fn apply_sets_only_the_fields_in_the_patch() {
+    let mut user = user();
+    let patch = UserPatch {
+        email: Some(Last::from(Some("ada@example.org".to_owned()))),
+        ..UserPatch::empty()
+    };
+
+    patch.apply(&mut user);
+
+    assert_eq!(Some("ada@example.org".to_owned()), user.email);
+    assert_eq!("ada", user.name);
+    assert_eq!(36, user.age);
+}
+
+#[test]
+fn later_patches_win() {
+    let patch = set_name("grace")
+        .combine(UserPatch {
+            age: Some(Last::from(37)),
+            ..UserPatch::empty()
+        })
+        .combine(set_name("hopper"));
+
+    let patched = patch.act(user());
+
+    assert_eq!("hopper", patched.name);
+    assert_eq!(37, patched.age);
+}
+
+#[test]
+fn empty_patch_changes_nothing() {
+    assert_eq!(user(), UserPatch::empty().act(user()));
+    assert_eq!(UserPatch::empty(), UserPatch::default());
+}
+
+#[test]
+fn combined_patches_act_like_applying_them_in_order() {
+    let first = set_name("grace");
+    let second = UserPatch {
+        name: Some(Last::from("hopper".to_owned())),
+        age: Some(Last::from(85)),
+        ..UserPatch::empty()
+    };
+
+    let one_by_one = second.act(first.act(user()));
+    let combined = first.combine(second).act(user());
+
+    assert_eq!(one_by_one, combined);
+}
+
+#[test]
+fn diff_turns_the_old_value_into_the_new_one() {
+    let old = user();
+    let new = User {
+        id: 2,
+        name: "ada".to_owned(),
+        email: Some("ada@example.org".to_owned()),
+        age: 37,
+    };
+
+    let patch = UserPatch::diff(&old, &new);
+
+    assert_eq!(None, patch.name);
+    assert_eq!(Some(Last::from(37)), patch.age);
+
+    let patched = patch.act(old);
+    assert_eq!(User { id: 1, ..new }, patched);
+}
+
+#[test]
+fn diff_of_equal_values_is_empty() {
+    assert_eq!(UserPatch::empty(), UserPatch::diff(&user(), &user()));
+}
+
+#[derive(Debug, PartialEq, Patch)]
+struct Pair<T> {
+    left: T,
+    right: Vec<T>,
+}
+
+#[test]
+fn generic_fields_are_patched() {
+    let mut pair = Pair {
+        left: 1,
+        right: vec![2],
+    };
+    let patch = PairPatch::diff(
+        &pair,
+        &Pair {
+            left: 1,
+            right: vec![3, 4],
+        },
+    );
+
+    patch.apply(&mut pair);
+
+    assert_eq!(vec![3, 4], pair.right);
+    assert!(patch.left.is_none());
+}
+
+#[derive(Debug, PartialEq, Patch)]
+struct Row<'a, K, V> {
+    #[patch(skip)]
+    key: K,
+    #[patch(skip)]
+    table: &'a str,
+    value: V,
+}
+
+#[test]
+fn parameters_only_used_by_skipped_fields_are_allowed() {
+    let mut row = Row {
+        key: "id",
+        table: "users",
+        value: 1,
+    };
+    let patch = RowPatch::diff(
+        &row,
+        &Row {
+            key: "other",
+            table: "users",
+            value: 2,
+        },
+    )
+    .combine(RowPatch::empty());
+
+    patch.apply(&mut row);
+
+    assert_eq!(("id", 2), (row.key, row.value));
+}
@@ -0,0 +1,61 @@
+// None of the traits are imported here, the derived code has to find them on its own.
+
+mod renamed {
+    pub use partial_functional::*;
+}
+
+mod hygiene {
+    use partfun_derive::{Monoid, Semigroup};
+    use partial_functional::{Max, Sum};
+
+    #[derive(Debug, PartialEq, Semigroup, Monoid)]
+    pub struct Stats<T> {
+        pub count: Sum<u32>,
+        #[semigroup(via = Max)]
+        pub largest: T,
+    }
+
+    #[derive(Debug, PartialEq, Semigroup, Monoid)]
+    #[partial_functional(crate = "crate::renamed")]
+    pub enum Total {
+        Count(Sum<u32>),
+        #[semigroup(identity)]
+        Nothing,
+    }
+
+    // A local trait with the same name must not be picked up by the derive.
+    #[allow(dead_code)]
+    trait Semigroup {
+        fn combine(self, rhs: Self) -> Self;
+    }
+}
+
+use partial_functional::prelude::*;
+
+#[test]
+fn derives_work_without_imports() {
+    let stats = hygiene::Stats {
+        count: Sum(1),
+        largest: 3u8,
+    }
+    .combine(hygiene::Stats {
+        count: Sum(1),
+        largest: 7,
+    });
+
+    assert_eq!(
+        hygiene::Stats {
+            count: Sum(2),
+            largest: 7
+        },
+        stats
+    );
+    assert_eq!(0, hygiene::Stats::<u8>::empty().largest);
+}
+
+#[test]
+fn crate_path_can_be_changed() {
+    let total = hygiene::Total::empty().combine(hygiene::Total::Count(Sum(2)));
+
+    assert_eq!(hygiene::Total::Count(Sum(2)), total);
+}
@@ -0,0 +1,9 @@
+use partfun_derive::Semigroup;
+
+#[derive(Semigroup)]
+struct Name {
+    #[semigroup(first, last)]
+    name: String,
+}
+
+fn main() {}
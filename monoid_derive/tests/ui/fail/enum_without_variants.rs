@@ -0,0 +1,6 @@
+use partfun_derive::Semigroup;
+
+#[derive(Semigroup)]
+enum Never {}
+
+fn main() {}
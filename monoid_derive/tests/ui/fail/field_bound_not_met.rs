@@ -0,0 +1,12 @@
+use partfun_derive::Semigroup;
+use partial_functional::prelude::*;
+
+#[derive(Semigroup)]
+struct Totals<T> {
+    sum: Sum<T>,
+}
+
+fn main() {
+    let totals = Totals { sum: Sum(String::from("a")) };
+    totals.combine(Totals { sum: Sum(String::from("b")) });
+}
@@ -0,0 +1,8 @@
+use partfun_derive::FunctorMut;
+
+#[derive(FunctorMut)]
+struct Outcome<T> {
+    result: Result<T, T>,
+}
+
+fn main() {}
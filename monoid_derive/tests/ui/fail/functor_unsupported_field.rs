@@ -0,0 +1,9 @@
+use partfun_derive::FunctorMut;
+
+#[derive(FunctorMut)]
+struct Callback<T> {
+    name: String,
+    call: fn(T) -> T,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use partfun_derive::FunctorMut;
+
+#[derive(FunctorMut)]
+struct Meters(f64);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+enum Order {
+    #[semigroup(into = Total)]
+    Line { quantity: Sum<u32> },
+    Total(Sum<u32>),
+}
+
+fn main() {}
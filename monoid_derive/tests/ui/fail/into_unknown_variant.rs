@@ -0,0 +1,11 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+enum Order {
+    #[semigroup(into = Totl)]
+    Line(Sum<u32>),
+    Total(Sum<u32>),
+}
+
+fn main() {}
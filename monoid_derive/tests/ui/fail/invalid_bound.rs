@@ -0,0 +1,8 @@
+use partfun_derive::Semigroup;
+use partial_functional::prelude::*;
+
+#[derive(Semigroup)]
+#[semigroup(bound = "T is Ord")]
+struct Largest<T>(Max<T>);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+#[semigroup(mixed = "newest")]
+enum Measure {
+    Length(Sum<u32>),
+    Weight(Sum<u32>),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+#[semigroup(mixed = "left")]
+struct Count(Sum<u32>);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use partfun_derive::Semigroup;
+use partial_functional::Max;
+
+#[derive(Semigroup)]
+#[semigroup(mixed = "left")]
+enum Keep {
+    A(Max<u32>),
+    B,
+}
+
+fn main() {}
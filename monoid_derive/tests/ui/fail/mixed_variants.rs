@@ -0,0 +1,10 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+enum Measure {
+    Length(Sum<u32>),
+    Weight(Sum<u32>),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::Sum;
+
+#[derive(Semigroup, Monoid)]
+struct Line {
+    #[semigroup(first)]
+    code: String,
+    quantity: Sum<u32>,
+}
+
+#[derive(Semigroup, Monoid)]
+struct Longest(#[semigroup(with = std::cmp::max)] u32);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::Sum;
+
+#[derive(Semigroup, Monoid)]
+enum Count {
+    Some(Sum<u32>),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use partfun_derive::Partial;
+
+#[derive(Partial)]
+struct Server {
+    port: u16,
+}
+
+#[derive(Partial)]
+struct Config {
+    #[partial(nested, default)]
+    server: Server,
+}
+
+fn main() {}
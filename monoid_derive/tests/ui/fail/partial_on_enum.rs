@@ -0,0 +1,9 @@
+use partfun_derive::Partial;
+
+#[derive(Partial)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use partfun_derive::Patch;
+
+#[derive(Patch)]
+struct Point(i32, i32);
+
+fn main() {}
@@ -0,0 +1,13 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+enum Count {
+    Some(Sum<u32>),
+    #[semigroup(identity)]
+    None,
+    #[semigroup(identity)]
+    Nothing,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use partfun_derive::Semigroup;
+
+#[derive(Semigroup)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partfun_derive::Semigroup;
+use partial_functional::Sum;
+
+#[derive(Semigroup)]
+struct Count {
+    #[semigroup(frist)]
+    count: Sum<u32>,
+}
+
+fn main() {}
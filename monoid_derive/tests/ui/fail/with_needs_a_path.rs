@@ -0,0 +1,9 @@
+use partfun_derive::Semigroup;
+
+#[derive(Semigroup)]
+struct Largest {
+    #[semigroup(with = "std::cmp::max")]
+    value: u32,
+}
+
+fn main() {}
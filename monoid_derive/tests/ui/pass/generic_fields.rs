@@ -0,0 +1,54 @@
+use std::marker::PhantomData;
+
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::prelude::*;
+
+// Needs `Sum<T>: Semigroup` and `Max<T>: Semigroup`, not `T: Semigroup`.
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Totals<T> {
+    sum: Sum<T>,
+    largest: Max<T>,
+    marker: PhantomData<T>,
+}
+
+// Neither field puts a bound on `T`.
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Tagged<T> {
+    tag: First<T>,
+    count: Sum<u32>,
+}
+
+// `std::cmp::max` needs an `Ord` bound that can't be inferred.
+#[derive(Debug, PartialEq, Semigroup)]
+#[semigroup(bound = "T: Ord")]
+struct Largest<T>(#[semigroup(with = std::cmp::max)] T);
+
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+#[semigroup(mixed = "priority")]
+enum Either<L, R> {
+    Left(Sum<L>),
+    Right(Last<R>),
+    #[semigroup(identity)]
+    Neither,
+}
+
+#[derive(Debug, PartialEq)]
+struct NoTraits;
+
+fn main() {
+    let totals = Totals { sum: Sum(2), largest: Max(2), marker: PhantomData }
+        .combine(Totals { sum: Sum(3), largest: Max(3), marker: PhantomData });
+    assert_eq!(Sum(5), totals.sum);
+    assert_eq!(Sum(0), Totals::<u32>::empty().sum);
+
+    let tagged = Tagged { tag: First::from(NoTraits), count: Sum(1) }
+        .combine(Tagged { tag: First(None), count: Sum(1) });
+    assert_eq!(First::from(NoTraits), tagged.tag);
+    assert_eq!(First(None), Tagged::<NoTraits>::empty().tag);
+
+    assert_eq!(Largest("b"), Largest("a").combine(Largest("b")));
+
+    let either = Either::<u8, NoTraits>::Left(Sum(1)).combine(Either::Right(Last::from(NoTraits)));
+    assert_eq!(Either::Left(Sum(1)), either);
+    assert_eq!(Either::<u8, NoTraits>::Neither, Either::empty());
+}
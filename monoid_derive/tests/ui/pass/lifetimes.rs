@@ -0,0 +1,35 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::{First, Semigroup, Monoid, Sum};
+
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Words<'a, T> {
+    words: Vec<&'a str>,
+    first: First<&'a str>,
+    total: Sum<T>,
+}
+
+#[derive(Debug, PartialEq, Semigroup)]
+struct Borrowed<'a>(#[semigroup(first)] &'a str, Sum<u32>);
+
+#[derive(Debug, PartialEq, Semigroup)]
+#[semigroup(mixed = "priority")]
+enum Token<'a> {
+    Word(Vec<&'a str>),
+    Number(Sum<i64>),
+}
+
+fn main() {
+    let text = String::from("hello world");
+    let mut split = text.split(' ');
+    let (hello, world) = (split.next().unwrap(), split.next().unwrap());
+
+    let words = Words { words: vec![hello], first: First::from(hello), total: Sum(1) }
+        .combine(Words { words: vec![world], first: First::from(world), total: Sum(1) });
+    assert_eq!(vec!["hello", "world"], words.words);
+    assert_eq!(First::from("hello"), words.first);
+    assert_eq!(Sum(0), Words::<u8>::empty().total);
+
+    assert_eq!(Borrowed(hello, Sum(2)), Borrowed(hello, Sum(1)).combine(Borrowed(world, Sum(1))));
+
+    assert_eq!(Token::Word(vec![hello]), Token::Word(vec![hello]).combine(Token::Number(Sum(1))));
+}
@@ -0,0 +1,27 @@
+use partfun_derive::{Monoid, Semigroup};
+use partial_functional::{Last, Semigroup, Monoid, Sum};
+
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Named {
+    count: Sum<u32>,
+    name: Last<String>,
+}
+
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Tuple(Sum<u32>, Vec<u8>);
+
+#[derive(Debug, PartialEq, Semigroup, Monoid)]
+struct Unit;
+
+fn main() {
+    let named = Named { count: Sum(1), name: Last::from(String::from("a")) }
+        .combine(Named { count: Sum(2), name: Last(None) });
+    assert_eq!(Named { count: Sum(3), name: Last::from(String::from("a")) }, named);
+    assert_eq!(Named { count: Sum(0), name: Last(None) }, Named::empty());
+
+    assert_eq!(Tuple(Sum(3), vec![1, 2]), Tuple(Sum(1), vec![1]).combine(Tuple(Sum(2), vec![2])));
+    assert_eq!(Tuple(Sum(0), vec![]), Tuple::empty());
+
+    assert_eq!(Unit, Unit.combine(Unit));
+    assert_eq!(Unit, Unit::empty());
+}
@@ -0,0 +1,138 @@
+use std::ops::{Add, Mul};
+
+use num_traits::FromPrimitive;
+
+use crate::monoid::{Last, Max, Min, Monoid, Sum};
+
+/// A monoid of updates that acts on values of type `T`.
+///
+/// Combining two updates gives an update that does both, the left one first and then the right one.
+/// Implementations has to satisfy these laws
+/// * `Self::empty().act(x) == x`
+/// * `first.combine(second).act(x) == second.act(first.act(x))`
+///
+/// Structures that apply an update to an aggregate instead of to each value, like the
+/// [LazySegmentTree](crate::collections::LazySegmentTree), also need the action to distribute over
+/// the combine of `T`, `a.act(x.combine(y)) == a.act(x).combine(a.act(y))`. That is why adding to a sum
+/// acts on a `(Sum<T>, Sum<usize>)` that keeps track of how many values went into the sum.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+///
+/// let add = Sum(3).combine(Sum(4));
+/// assert_eq!(Min(12), add.act(Min(5)));
+///
+/// let assign = Last::from(2).combine(Last::from(9));
+/// assert_eq!((Sum(27), Sum(3)), assign.act((Sum(5), Sum(3))));
+/// ```
+pub trait MonoidAction<T>: Monoid {
+    fn act(&self, target: T) -> T;
+}
+
+/// Adds to the minimum.
+impl<T> MonoidAction<Min<T>> for Sum<T>
+where
+    Sum<T>: Monoid,
+    T: Clone + Add<Output = T>,
+{
+    fn act(&self, target: Min<T>) -> Min<T> {
+        Min(target.0 + self.0.clone())
+    }
+}
+
+/// Adds to the maximum.
+impl<T> MonoidAction<Max<T>> for Sum<T>
+where
+    Sum<T>: Monoid,
+    T: Clone + Add<Output = T>,
+{
+    fn act(&self, target: Max<T>) -> Max<T> {
+        Max(target.0 + self.0.clone())
+    }
+}
+
+/// Adds to every value that went into the sum.
+impl<T> MonoidAction<(Sum<T>, Sum<usize>)> for Sum<T>
+where
+    Sum<T>: Monoid,
+    T: Clone + Add<Output = T> + Mul<Output = T> + FromPrimitive,
+{
+    fn act(&self, (sum, len): (Sum<T>, Sum<usize>)) -> (Sum<T>, Sum<usize>) {
+        (Sum(sum.0 + self.0.clone() * times(len.0)), len)
+    }
+}
+
+/// Assigns the minimum.
+impl<T: Clone> MonoidAction<Min<T>> for Last<T> {
+    fn act(&self, target: Min<T>) -> Min<T> {
+        self.0.clone().map_or(target, Min)
+    }
+}
+
+/// Assigns the maximum.
+impl<T: Clone> MonoidAction<Max<T>> for Last<T> {
+    fn act(&self, target: Max<T>) -> Max<T> {
+        self.0.clone().map_or(target, Max)
+    }
+}
+
+/// Assigns every value that went into the sum.
+impl<T> MonoidAction<(Sum<T>, Sum<usize>)> for Last<T>
+where
+    T: Clone + Mul<Output = T> + FromPrimitive,
+{
+    fn act(&self, (sum, len): (Sum<T>, Sum<usize>)) -> (Sum<T>, Sum<usize>) {
+        match self.0.clone() {
+            Some(value) => (Sum(value * times(len.0)), len),
+            None => (sum, len),
+        }
+    }
+}
+
+fn times<T: FromPrimitive>(len: usize) -> T {
+    T::from_usize(len).expect("the length has to be representable by the summed type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::semigroup::Semigroup;
+
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn empty_sum_is_identity(x: i32) -> bool {
+        Sum::<i64>::empty().act(Min(x as i64)) == Min(x as i64)
+    }
+
+    #[quickcheck]
+    fn empty_last_is_identity(x: i32, len: u8) -> bool {
+        let target = (Sum(x as i64), Sum(len as usize));
+
+        Last::<i64>::empty().act(target) == target
+    }
+
+    #[quickcheck]
+    fn sum_composition_law(a: i16, b: i16, x: i16) -> bool {
+        let (a, b, x) = (Sum(a as i64), Sum(b as i64), Max(x as i64));
+
+        a.combine(b).act(x) == b.act(a.act(x))
+    }
+
+    #[quickcheck]
+    fn last_composition_law(a: Option<i16>, b: Option<i16>, x: i16) -> bool {
+        let (a, b, x) = (Last(a), Last(b), Min(x));
+
+        a.combine(b).act(x) == b.act(a.act(x))
+    }
+
+    #[quickcheck]
+    fn sum_distributes_over_sum_with_length(a: i16, x: i16, y: i16) -> bool {
+        let a = Sum(a as i64);
+        let (x, y) = ((Sum(x as i64), Sum(1)), (Sum(y as i64), Sum(1)));
+
+        a.act(x.combine(y)) == a.act(x).combine(a.act(y))
+    }
+}
@@ -0,0 +1,237 @@
+use crate::semigroup::Semigroup;
+
+use super::MonoidStack;
+
+/// A first in, first out queue that keeps the combination of all of its elements.
+///
+/// The queue is made of two [MonoidStack]s, new elements are pushed on the back stack and popped from the front
+/// stack. When the front runs out, the back is moved over to it in one go, so every element is moved at most once
+/// and all operations are amortised O(1). This works for any [Semigroup], it doesn't have to be commutative nor
+/// have inverses.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::AggQueue;
+///
+/// let mut queue = AggQueue::new();
+/// queue.push_back(String::from("a"));
+/// queue.push_back(String::from("b"));
+/// queue.push_back(String::from("c"));
+/// assert_eq!(Some(String::from("abc")), queue.aggregate());
+///
+/// queue.pop_front();
+/// assert_eq!(Some(String::from("bc")), queue.aggregate());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggQueue<S> {
+    front: MonoidStack<S>,
+    back: MonoidStack<S>,
+}
+
+impl<S: Semigroup + Clone> AggQueue<S> {
+    pub fn new() -> Self {
+        Self {
+            front: MonoidStack::top_down(),
+            back: MonoidStack::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+
+    pub fn push_back(&mut self, value: S) {
+        self.back.push(value);
+    }
+
+    pub fn pop_front(&mut self) -> Option<S> {
+        if self.front.is_empty() {
+            while let Some(value) = self.back.pop() {
+                self.front.push(value);
+            }
+        }
+
+        self.front.pop()
+    }
+
+    pub fn front(&self) -> Option<&S> {
+        self.front.peek().or_else(|| self.back.iter().next())
+    }
+
+    /// Returns the combination of all elements from the front to the back, or None if the queue is empty.
+    pub fn aggregate(&self) -> Option<S> {
+        self.front
+            .aggregate()
+            .cloned()
+            .combine(self.back.aggregate().cloned())
+    }
+}
+
+impl<S: Semigroup + Clone> Default for AggQueue<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Semigroup + Clone> Extend<S> for AggQueue<S> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<S: Semigroup + Clone> FromIterator<S> for AggQueue<S> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+/// The aggregate of the last `capacity` elements pushed to it, like a rolling maximum over the latest prices.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::SlidingWindow;
+///
+/// let mut window = SlidingWindow::new(3);
+/// let rolling_max = [4, 2, 12, 3, 1, 5]
+///     .into_iter()
+///     .map(|price| {
+///         window.push(Max(price));
+///         window.aggregate().unwrap().0
+///     })
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(vec![4, 4, 12, 12, 12, 5], rolling_max);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlidingWindow<S> {
+    queue: AggQueue<S>,
+    capacity: usize,
+}
+
+impl<S: Semigroup + Clone> SlidingWindow<S> {
+    /// Creates a window over the last `capacity` elements.
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a sliding window needs room for at least one element"
+        );
+
+        Self {
+            queue: AggQueue::new(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.queue.len() == self.capacity
+    }
+
+    /// Adds `value` to the window, returning the oldest element if it fell out of the window.
+    pub fn push(&mut self, value: S) -> Option<S> {
+        self.queue.push_back(value);
+
+        if self.queue.len() > self.capacity {
+            self.queue.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the combination of all elements in the window, or None if nothing has been pushed.
+    pub fn aggregate(&self) -> Option<S> {
+        self.queue.aggregate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{Max, Sum};
+
+    use std::collections::VecDeque;
+
+    use quickcheck_macros::quickcheck;
+
+    fn naive<S: Semigroup + Clone>(values: &VecDeque<S>) -> Option<S> {
+        values.iter().cloned().reduce(|acc, x| acc.combine(x))
+    }
+
+    #[quickcheck]
+    fn queue_agrees_with_fold(ops: Vec<Option<String>>) -> bool {
+        let mut queue = AggQueue::new();
+        let mut model = VecDeque::new();
+
+        ops.into_iter().all(|op| {
+            match op {
+                Some(value) => {
+                    queue.push_back(value.clone());
+                    model.push_back(value);
+                }
+                None => {
+                    if queue.pop_front() != model.pop_front() {
+                        return false;
+                    }
+                }
+            }
+
+            queue.len() == model.len()
+                && queue.front() == model.front()
+                && queue.aggregate() == naive(&model)
+        })
+    }
+
+    #[quickcheck]
+    fn window_agrees_with_fold(values: Vec<u32>, capacity: u8) -> bool {
+        let capacity = capacity as usize % 8 + 1;
+        let mut window = SlidingWindow::new(capacity);
+
+        values.iter().enumerate().all(|(i, &value)| {
+            window.push((Max(value), Sum(value as u64)));
+
+            let start = (i + 1).saturating_sub(capacity);
+            let model = values[start..=i]
+                .iter()
+                .map(|&x| (Max(x), Sum(x as u64)))
+                .collect::<VecDeque<_>>();
+
+            window.aggregate() == naive(&model)
+        })
+    }
+
+    #[test]
+    fn window_returns_evicted_elements() {
+        let mut window = SlidingWindow::new(2);
+
+        assert_eq!(None, window.push(Sum(1)));
+        assert_eq!(None, window.push(Sum(2)));
+        assert!(window.is_full());
+        assert_eq!(Some(Sum(1)), window.push(Sum(3)));
+        assert_eq!(Some(Sum(5)), window.aggregate());
+    }
+}
@@ -0,0 +1,242 @@
+use std::ops::RangeBounds;
+
+use crate::group::Group;
+
+use super::bounds;
+
+/// A Fenwick tree, or binary indexed tree, keeps prefix aggregates of a [Group] so that both adding to an element
+/// and combining a prefix takes O(log n) combines. Any range is then the difference of two prefixes.
+///
+/// The tree combines values in a different order than they are stored in, so the group has to be commutative like
+/// [Sum](crate::Sum) or [Xor](crate::Xor).
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::Fenwick;
+///
+/// let mut tree = [3, 1, 4, 1, 5].into_iter().map(Sum).collect::<Fenwick<_>>();
+///
+/// assert_eq!(Sum(8), tree.prefix(3));
+/// assert_eq!(Sum(5), tree.range(1..3));
+///
+/// tree.add(1, Sum(10));
+/// assert_eq!(Sum(15), tree.range(1..3));
+/// assert_eq!(Some(3), tree.lower_bound(&Sum(15)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fenwick<G> {
+    tree: Vec<G>,
+}
+
+impl<G: Group + Clone> Fenwick<G> {
+    /// Creates a tree of `len` elements that are all [Monoid::empty](crate::Monoid::empty).
+    pub fn new(len: usize) -> Self {
+        Self {
+            tree: vec![G::empty(); len + 1],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Combines `value` into the element at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn add(&mut self, index: usize, value: G) {
+        assert!(
+            index < self.len(),
+            "index {index} out of bounds for length {}",
+            self.len()
+        );
+
+        let mut node = index + 1;
+        while node < self.tree.len() {
+            let current = std::mem::replace(&mut self.tree[node], G::empty());
+            self.tree[node] = current.combine(value.clone());
+            node += lowest_bit(node);
+        }
+    }
+
+    /// Returns the combination of the first `len` elements.
+    ///
+    /// # Panics
+    /// If `len` is larger than the tree.
+    pub fn prefix(&self, len: usize) -> G {
+        assert!(
+            len <= self.len(),
+            "prefix {len} out of bounds for length {}",
+            self.len()
+        );
+
+        let mut acc = G::empty();
+        let mut node = len;
+        while node > 0 {
+            acc = acc.combine(self.tree[node].clone());
+            node -= lowest_bit(node);
+        }
+
+        acc
+    }
+
+    /// Returns the combination of all elements in `range`.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> G {
+        let (start, end) = bounds(range, self.len());
+
+        self.prefix(end).combine(self.prefix(start).invert())
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn get(&self, index: usize) -> G {
+        self.range(index..=index)
+    }
+
+    /// Returns the smallest `len` whose [Fenwick::prefix] is at least `value`, or None if not even all of the
+    /// elements add up to it.
+    ///
+    /// The prefixes has to be non-decreasing for the search to make sense, like a [Sum](crate::Sum) of
+    /// non-negative numbers.
+    pub fn lower_bound(&self, value: &G) -> Option<usize>
+    where
+        G: Ord,
+    {
+        if G::empty() >= *value {
+            return Some(0);
+        }
+
+        let len = self.len();
+        let mut pos = 0;
+        let mut acc = G::empty();
+        let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+        while step > 0 {
+            if pos + step <= len {
+                let next = acc.clone().combine(self.tree[pos + step].clone());
+                if next < *value {
+                    pos += step;
+                    acc = next;
+                }
+            }
+
+            step >>= 1;
+        }
+
+        (pos < len).then_some(pos + 1)
+    }
+}
+
+impl<G: Group + Clone> FromIterator<G> for Fenwick<G> {
+    fn from_iter<I: IntoIterator<Item = G>>(iter: I) -> Self {
+        let mut tree = std::iter::once(G::empty()).chain(iter).collect::<Vec<_>>();
+
+        for node in 1..tree.len() {
+            let parent = node + lowest_bit(node);
+            if parent < tree.len() {
+                let child = tree[node].clone();
+                let current = std::mem::replace(&mut tree[parent], G::empty());
+                tree[parent] = current.combine(child);
+            }
+        }
+
+        Self { tree }
+    }
+}
+
+fn lowest_bit(node: usize) -> usize {
+    node & node.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{Monoid, Sum, Xor};
+
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    fn naive<G: Monoid + Clone>(values: &[G]) -> G {
+        values
+            .iter()
+            .cloned()
+            .fold(G::empty(), |acc, x| acc.combine(x))
+    }
+
+    fn agrees_with_fold<G: Group + Clone + PartialEq>(
+        mut values: Vec<G>,
+        updates: Vec<(usize, G)>,
+    ) -> TestResult {
+        if values.is_empty() {
+            return TestResult::discard();
+        }
+
+        let mut tree = values.iter().cloned().collect::<Fenwick<_>>();
+        for (index, value) in updates {
+            let index = index % values.len();
+            values[index] = values[index].clone().combine(value.clone());
+            tree.add(index, value);
+        }
+
+        let len = values.len();
+        let prefixes = (0..=len).all(|end| tree.prefix(end) == naive(&values[..end]));
+        let ranges = (0..=len).all(|start| {
+            (start..=len).all(|end| tree.range(start..end) == naive(&values[start..end]))
+        });
+
+        TestResult::from_bool(prefixes && ranges)
+    }
+
+    #[quickcheck]
+    fn sum_agrees_with_fold(values: Vec<i32>, updates: Vec<(usize, i32)>) -> TestResult {
+        agrees_with_fold(
+            values.into_iter().map(|x| Sum(x as i64)).collect(),
+            updates
+                .into_iter()
+                .map(|(i, x)| (i, Sum(x as i64)))
+                .collect(),
+        )
+    }
+
+    #[quickcheck]
+    fn xor_agrees_with_fold(values: Vec<Xor<u32>>, updates: Vec<(usize, Xor<u32>)>) -> TestResult {
+        agrees_with_fold(values, updates)
+    }
+
+    #[quickcheck]
+    fn lower_bound_finds_the_first_prefix_reaching_the_value(
+        values: Vec<u16>,
+        target: u32,
+    ) -> bool {
+        let values = values
+            .into_iter()
+            .map(|x| Sum(x as i64))
+            .collect::<Vec<_>>();
+        let tree = values.iter().copied().collect::<Fenwick<_>>();
+        let target = Sum(target as i64 % (naive(&values).0 + 2));
+
+        let expected = (0..=values.len()).find(|&len| naive(&values[..len]) >= target);
+
+        tree.lower_bound(&target) == expected
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree = Fenwick::<Sum<i64>>::new(0);
+
+        assert!(tree.is_empty());
+        assert_eq!(Sum(0), tree.prefix(0));
+        assert_eq!(Some(0), tree.lower_bound(&Sum(0)));
+        assert_eq!(None, tree.lower_bound(&Sum(1)));
+    }
+}
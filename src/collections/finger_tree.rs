@@ -0,0 +1,813 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::monoid::Monoid;
+use crate::semigroup::Semigroup;
+
+/// A value that can be summarized by a [Monoid], the measure of a sequence is the combination of the measures of
+/// its elements.
+///
+/// Every monoid measures itself, so a `FingerTree<Max<u32>, Max<u32>>` can be used as is.
+pub trait Measured<M> {
+    fn measure(&self) -> M;
+}
+
+impl<M: Monoid + Clone> Measured<M> for M {
+    fn measure(&self) -> M {
+        self.clone()
+    }
+}
+
+/// A persistent sequence where every subtree caches the [Measured] monoid of its elements.
+///
+/// Pushing and popping at either end is amortised O(1), concatenating two trees is O(log n) and so is splitting
+/// the tree where a predicate on the accumulated measure flips from false to true. Updates never change a tree,
+/// they return a new one sharing most of its structure with the old one, so cloning is cheap.
+///
+/// The measure decides what the tree is good at, measuring every element as `Sum(1)` gives a sequence that can be
+/// split at an index while [Max](crate::Max) gives a priority queue.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::{FingerTree, Measured};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Letter(char);
+///
+/// impl Measured<Sum<usize>> for Letter {
+///     fn measure(&self) -> Sum<usize> {
+///         Sum(1)
+///     }
+/// }
+///
+/// let tree = "hello".chars().map(Letter).collect::<FingerTree<Sum<usize>, _>>();
+/// let (left, right) = tree.split(|len| len.0 > 2);
+///
+/// assert_eq!(Sum(5), tree.measure());
+/// assert_eq!(Sum(2), left.measure());
+/// assert_eq!(Some(&Letter('l')), right.front());
+/// ```
+pub struct FingerTree<M, T> {
+    root: Tree<M, T>,
+}
+
+enum Node<M, T> {
+    Leaf(T),
+    // Two or three children, all of them at the same depth
+    Branch(M, Vec<Rc<Node<M, T>>>),
+}
+
+// The elements of a tree one level down in the spine are branches of the elements above it, so a digit holds leaves
+// at the top of the spine and branches below it.
+type Digit<M, T> = Vec<Rc<Node<M, T>>>;
+
+// A digit split around one of its nodes.
+type SplitDigit<M, T> = (Digit<M, T>, Rc<Node<M, T>>, Digit<M, T>);
+
+enum Tree<M, T> {
+    Empty,
+    Single(Rc<Node<M, T>>),
+    Deep {
+        measure: M,
+        prefix: Digit<M, T>,
+        middle: Rc<Tree<M, T>>,
+        suffix: Digit<M, T>,
+    },
+}
+
+impl<M, T> Clone for Tree<M, T>
+where
+    M: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Tree::Empty => Tree::Empty,
+            Tree::Single(node) => Tree::Single(node.clone()),
+            Tree::Deep {
+                measure,
+                prefix,
+                middle,
+                suffix,
+            } => Tree::Deep {
+                measure: measure.clone(),
+                prefix: prefix.clone(),
+                middle: middle.clone(),
+                suffix: suffix.clone(),
+            },
+        }
+    }
+}
+
+impl<M, T> Node<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn branch(children: Vec<Rc<Self>>) -> Rc<Self> {
+        Rc::new(Node::Branch(measure_digit(&children), children))
+    }
+
+    fn measure(&self) -> M {
+        match self {
+            Node::Leaf(value) => value.measure(),
+            Node::Branch(measure, _) => measure.clone(),
+        }
+    }
+
+    fn children(&self) -> Digit<M, T> {
+        match self {
+            Node::Leaf(_) => unreachable!("only branches are stored below the top of the spine"),
+            Node::Branch(_, children) => children.clone(),
+        }
+    }
+}
+
+fn measure_digit<M, T>(digit: &[Rc<Node<M, T>>]) -> M
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    digit
+        .iter()
+        .fold(M::empty(), |acc, node| acc.combine(node.measure()))
+}
+
+impl<M, T> Tree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn deep(prefix: Digit<M, T>, middle: Rc<Self>, suffix: Digit<M, T>) -> Self {
+        let measure = measure_digit(&prefix)
+            .combine(middle.measure())
+            .combine(measure_digit(&suffix));
+
+        Tree::Deep {
+            measure,
+            prefix,
+            middle,
+            suffix,
+        }
+    }
+
+    fn from_digit(digit: Digit<M, T>) -> Self {
+        digit
+            .into_iter()
+            .fold(Tree::Empty, |tree, node| tree.push_back(node))
+    }
+
+    fn measure(&self) -> M {
+        match self {
+            Tree::Empty => M::empty(),
+            Tree::Single(node) => node.measure(),
+            Tree::Deep { measure, .. } => measure.clone(),
+        }
+    }
+
+    fn push_front(&self, node: Rc<Node<M, T>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(node),
+            Tree::Single(other) => {
+                Self::deep(vec![node], Rc::new(Tree::Empty), vec![other.clone()])
+            }
+            Tree::Deep {
+                prefix,
+                middle,
+                suffix,
+                ..
+            } => {
+                if let [a, b, c, d] = prefix.as_slice() {
+                    let branch = Node::branch(vec![b.clone(), c.clone(), d.clone()]);
+                    Self::deep(
+                        vec![node, a.clone()],
+                        Rc::new(middle.push_front(branch)),
+                        suffix.clone(),
+                    )
+                } else {
+                    let prefix = std::iter::once(node)
+                        .chain(prefix.iter().cloned())
+                        .collect();
+                    Self::deep(prefix, middle.clone(), suffix.clone())
+                }
+            }
+        }
+    }
+
+    fn push_back(&self, node: Rc<Node<M, T>>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(node),
+            Tree::Single(other) => {
+                Self::deep(vec![other.clone()], Rc::new(Tree::Empty), vec![node])
+            }
+            Tree::Deep {
+                prefix,
+                middle,
+                suffix,
+                ..
+            } => {
+                if let [a, b, c, d] = suffix.as_slice() {
+                    let branch = Node::branch(vec![a.clone(), b.clone(), c.clone()]);
+                    Self::deep(
+                        prefix.clone(),
+                        Rc::new(middle.push_back(branch)),
+                        vec![d.clone(), node],
+                    )
+                } else {
+                    let mut suffix = suffix.clone();
+                    suffix.push(node);
+                    Self::deep(prefix.clone(), middle.clone(), suffix)
+                }
+            }
+        }
+    }
+
+    fn view_front(&self) -> Option<(Rc<Node<M, T>>, Self)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(node) => Some((node.clone(), Tree::Empty)),
+            Tree::Deep {
+                prefix,
+                middle,
+                suffix,
+                ..
+            } => Some((
+                prefix[0].clone(),
+                Self::deep_front(prefix[1..].to_vec(), middle, suffix.clone()),
+            )),
+        }
+    }
+
+    fn view_back(&self) -> Option<(Self, Rc<Node<M, T>>)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(node) => Some((Tree::Empty, node.clone())),
+            Tree::Deep {
+                prefix,
+                middle,
+                suffix,
+                ..
+            } => Some((
+                Self::deep_back(prefix.clone(), middle, suffix[..suffix.len() - 1].to_vec()),
+                suffix[suffix.len() - 1].clone(),
+            )),
+        }
+    }
+
+    // Builds a deep tree whose prefix might be empty by borrowing a branch from the middle.
+    fn deep_front(prefix: Digit<M, T>, middle: &Rc<Self>, suffix: Digit<M, T>) -> Self {
+        if !prefix.is_empty() {
+            return Self::deep(prefix, middle.clone(), suffix);
+        }
+
+        match middle.view_front() {
+            None => Self::from_digit(suffix),
+            Some((branch, middle)) => Self::deep(branch.children(), Rc::new(middle), suffix),
+        }
+    }
+
+    // Builds a deep tree whose suffix might be empty by borrowing a branch from the middle.
+    fn deep_back(prefix: Digit<M, T>, middle: &Rc<Self>, suffix: Digit<M, T>) -> Self {
+        if !suffix.is_empty() {
+            return Self::deep(prefix, middle.clone(), suffix);
+        }
+
+        match middle.view_back() {
+            None => Self::from_digit(prefix),
+            Some((middle, branch)) => Self::deep(prefix, Rc::new(middle), branch.children()),
+        }
+    }
+
+    // Concatenates two trees with some loose nodes between them.
+    fn append(&self, between: Digit<M, T>, other: &Self) -> Self {
+        match (self, other) {
+            (Tree::Empty, _) => between
+                .into_iter()
+                .rev()
+                .fold(other.clone(), |tree, node| tree.push_front(node)),
+            (_, Tree::Empty) => between
+                .into_iter()
+                .fold(self.clone(), |tree, node| tree.push_back(node)),
+            (Tree::Single(node), _) => Tree::Empty.append(between, other).push_front(node.clone()),
+            (_, Tree::Single(node)) => self.append(between, &Tree::Empty).push_back(node.clone()),
+            (
+                Tree::Deep {
+                    prefix,
+                    middle: left,
+                    suffix,
+                    ..
+                },
+                Tree::Deep {
+                    prefix: other_prefix,
+                    middle: right,
+                    suffix: other_suffix,
+                    ..
+                },
+            ) => {
+                let loose = suffix
+                    .iter()
+                    .cloned()
+                    .chain(between)
+                    .chain(other_prefix.iter().cloned())
+                    .collect::<Vec<_>>();
+
+                Self::deep(
+                    prefix.clone(),
+                    Rc::new(left.append(branches(loose), right)),
+                    other_suffix.clone(),
+                )
+            }
+        }
+    }
+
+    // Splits a non-empty tree around the node where `pred(acc + measure up to and including the node)` first
+    // holds, or around the last node if it never does.
+    fn split<P>(&self, pred: &mut P, acc: M) -> (Self, Rc<Node<M, T>>, Self)
+    where
+        P: FnMut(&M) -> bool,
+    {
+        match self {
+            Tree::Empty => unreachable!("empty trees are never split"),
+            Tree::Single(node) => (Tree::Empty, node.clone(), Tree::Empty),
+            Tree::Deep {
+                prefix,
+                middle,
+                suffix,
+                ..
+            } => {
+                let after_prefix = acc.clone().combine(measure_digit(prefix));
+                if pred(&after_prefix) {
+                    let (left, node, right) = split_digit(prefix, pred, acc);
+                    return (
+                        Self::from_digit(left),
+                        node,
+                        Self::deep_front(right, middle, suffix.clone()),
+                    );
+                }
+
+                let after_middle = after_prefix.clone().combine(middle.measure());
+                if pred(&after_middle) {
+                    let (left_middle, branch, right_middle) =
+                        middle.split(pred, after_prefix.clone());
+                    let acc = after_prefix.combine(left_middle.measure());
+                    let (left, node, right) = split_digit(&branch.children(), pred, acc);
+
+                    return (
+                        Self::deep_back(prefix.clone(), &Rc::new(left_middle), left),
+                        node,
+                        Self::deep_front(right, &Rc::new(right_middle), suffix.clone()),
+                    );
+                }
+
+                let (left, node, right) = split_digit(suffix, pred, after_middle);
+                (
+                    Self::deep_back(prefix.clone(), middle, left),
+                    node,
+                    Self::from_digit(right),
+                )
+            }
+        }
+    }
+}
+
+// Groups loose nodes into branches of two or three.
+fn branches<M, T>(mut nodes: Vec<Rc<Node<M, T>>>) -> Digit<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    let mut result = Vec::new();
+    while !nodes.is_empty() {
+        let take = match nodes.len() {
+            2 | 4 => 2,
+            _ => 3,
+        };
+
+        let rest = nodes.split_off(take);
+        result.push(Node::branch(nodes));
+        nodes = rest;
+    }
+
+    result
+}
+
+fn split_digit<M, T, P>(digit: &[Rc<Node<M, T>>], pred: &mut P, mut acc: M) -> SplitDigit<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+    P: FnMut(&M) -> bool,
+{
+    for (i, node) in digit.iter().enumerate().take(digit.len() - 1) {
+        acc = acc.combine(node.measure());
+        if pred(&acc) {
+            return (digit[..i].to_vec(), node.clone(), digit[i + 1..].to_vec());
+        }
+    }
+
+    (
+        digit[..digit.len() - 1].to_vec(),
+        digit[digit.len() - 1].clone(),
+        Vec::new(),
+    )
+}
+
+impl<M, T> FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    pub fn new() -> Self {
+        Self { root: Tree::Empty }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self.root, Tree::Empty)
+    }
+
+    /// Returns the combination of the measures of all elements.
+    pub fn measure(&self) -> M {
+        self.root.measure()
+    }
+
+    pub fn push_front(&self, value: T) -> Self {
+        Self {
+            root: self.root.push_front(Rc::new(Node::Leaf(value))),
+        }
+    }
+
+    pub fn push_back(&self, value: T) -> Self {
+        Self {
+            root: self.root.push_back(Rc::new(Node::Leaf(value))),
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        match &self.root {
+            Tree::Empty => None,
+            Tree::Single(node) => Some(leftmost(node)),
+            Tree::Deep { prefix, .. } => Some(leftmost(&prefix[0])),
+        }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        match &self.root {
+            Tree::Empty => None,
+            Tree::Single(node) => Some(rightmost(node)),
+            Tree::Deep { suffix, .. } => Some(rightmost(&suffix[suffix.len() - 1])),
+        }
+    }
+
+    /// Returns the first element and the tree without it.
+    pub fn pop_front(&self) -> Option<(T, Self)>
+    where
+        T: Clone,
+    {
+        self.root
+            .view_front()
+            .map(|(node, root)| (leaf(&node).clone(), Self { root }))
+    }
+
+    /// Returns the tree without its last element and the element.
+    pub fn pop_back(&self) -> Option<(Self, T)>
+    where
+        T: Clone,
+    {
+        self.root
+            .view_back()
+            .map(|(root, node)| (Self { root }, leaf(&node).clone()))
+    }
+
+    /// Returns a tree with the elements of `self` followed by the elements of `other`.
+    pub fn concat(&self, other: &Self) -> Self {
+        Self {
+            root: self.root.append(Vec::new(), &other.root),
+        }
+    }
+
+    /// Splits the tree in two where `pred` on the measure of the elements up to and including an element first
+    /// holds, that element is the first one in the right tree.
+    ///
+    /// The predicate has to be monotone, once it is true for a prefix it has to be true for all longer prefixes.
+    /// If it never holds, all elements ends up in the left tree.
+    pub fn split<P: FnMut(&M) -> bool>(&self, mut pred: P) -> (Self, Self) {
+        if self.is_empty() || !pred(&self.measure()) {
+            return (self.clone(), Self::new());
+        }
+
+        let (left, node, right) = self.root.split(&mut pred, M::empty());
+        (
+            Self { root: left },
+            Self {
+                root: right.push_front(node),
+            },
+        )
+    }
+
+    /// Iterates over the elements from the front to the back.
+    pub fn iter(&self) -> Iter<'_, M, T> {
+        Iter {
+            stack: vec![Frame::Tree(&self.root)],
+        }
+    }
+}
+
+fn leaf<M, T>(node: &Node<M, T>) -> &T {
+    match node {
+        Node::Leaf(value) => value,
+        Node::Branch(..) => unreachable!("only leaves are stored at the top of the spine"),
+    }
+}
+
+fn leftmost<M, T>(mut node: &Node<M, T>) -> &T {
+    loop {
+        match node {
+            Node::Leaf(value) => return value,
+            Node::Branch(_, children) => node = &children[0],
+        }
+    }
+}
+
+fn rightmost<M, T>(mut node: &Node<M, T>) -> &T {
+    loop {
+        match node {
+            Node::Leaf(value) => return value,
+            Node::Branch(_, children) => node = &children[children.len() - 1],
+        }
+    }
+}
+
+/// An iterator over the elements of a [FingerTree], created by [FingerTree::iter].
+pub struct Iter<'a, M, T> {
+    stack: Vec<Frame<'a, M, T>>,
+}
+
+enum Frame<'a, M, T> {
+    Tree(&'a Tree<M, T>),
+    Node(&'a Node<M, T>),
+}
+
+impl<'a, M, T> Iterator for Iter<'a, M, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                Frame::Node(Node::Leaf(value)) => return Some(value),
+                Frame::Node(Node::Branch(_, children)) => {
+                    self.stack
+                        .extend(children.iter().rev().map(|node| Frame::Node(node)));
+                }
+                Frame::Tree(Tree::Empty) => {}
+                Frame::Tree(Tree::Single(node)) => self.stack.push(Frame::Node(node)),
+                Frame::Tree(Tree::Deep {
+                    prefix,
+                    middle,
+                    suffix,
+                    ..
+                }) => {
+                    self.stack
+                        .extend(suffix.iter().rev().map(|node| Frame::Node(node)));
+                    self.stack.push(Frame::Tree(middle));
+                    self.stack
+                        .extend(prefix.iter().rev().map(|node| Frame::Node(node)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<M: Clone, T> Clone for FingerTree<M, T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<M, T> Default for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M, T> Semigroup for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn combine(self, rhs: Self) -> Self {
+        self.concat(&rhs)
+    }
+}
+
+impl<M, T> FromIterator<T> for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |tree, value| tree.push_back(value))
+    }
+}
+
+impl<M, T> Extend<T> for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            *self = self.push_back(value);
+        }
+    }
+}
+
+impl<'a, M, T> IntoIterator for &'a FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M>,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, M, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<M, T> PartialEq for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M> + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<M, T> Eq for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M> + Eq,
+{
+}
+
+impl<M, T> fmt::Debug for FingerTree<M, T>
+where
+    M: Monoid + Clone,
+    T: Measured<M> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{Max, Sum};
+
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Item(u32);
+
+    impl Measured<Sum<usize>> for Item {
+        fn measure(&self) -> Sum<usize> {
+            Sum(1)
+        }
+    }
+
+    type Seq = FingerTree<Sum<usize>, Item>;
+
+    fn seq(values: &[u32]) -> Seq {
+        values.iter().copied().map(Item).collect()
+    }
+
+    fn values(tree: &Seq) -> Vec<u32> {
+        tree.iter().map(|item| item.0).collect()
+    }
+
+    #[quickcheck]
+    fn from_iter_keeps_the_order(xs: Vec<u32>) -> bool {
+        let tree = seq(&xs);
+
+        values(&tree) == xs && tree.measure() == Sum(xs.len())
+    }
+
+    #[quickcheck]
+    fn push_front_and_pop_back_is_a_queue(xs: Vec<u32>) -> bool {
+        let mut tree = xs
+            .iter()
+            .copied()
+            .map(Item)
+            .fold(Seq::new(), |tree, x| tree.push_front(x));
+
+        let mut popped = Vec::new();
+        while let Some((rest, item)) = tree.pop_back() {
+            popped.push(item.0);
+            tree = rest;
+        }
+
+        popped == xs && tree.is_empty()
+    }
+
+    #[quickcheck]
+    fn pop_front_returns_the_elements_in_order(xs: Vec<u32>) -> bool {
+        let mut tree = seq(&xs);
+
+        let mut popped = Vec::new();
+        while let Some((item, rest)) = tree.pop_front() {
+            if tree.front() != Some(&item) {
+                return false;
+            }
+
+            popped.push(item.0);
+            tree = rest;
+        }
+
+        popped == xs
+    }
+
+    #[quickcheck]
+    fn concat_is_the_same_as_appending(xs: Vec<u32>, ys: Vec<u32>) -> bool {
+        let tree = seq(&xs).concat(&seq(&ys));
+        let expected = xs.iter().chain(&ys).copied().collect::<Vec<_>>();
+
+        values(&tree) == expected
+            && tree.measure() == Sum(expected.len())
+            && tree.back().map(|x| x.0) == expected.last().copied()
+    }
+
+    #[quickcheck]
+    fn split_at_index(xs: Vec<u32>, ys: Vec<u32>, index: usize) -> TestResult {
+        // Build the tree from a concatenation so that splits also runs through deeper nodes
+        let tree = seq(&xs).concat(&seq(&ys));
+        let all = xs.iter().chain(&ys).copied().collect::<Vec<_>>();
+        if all.is_empty() {
+            return TestResult::discard();
+        }
+
+        let index = index % (all.len() + 1);
+        let (left, right) = tree.split(|len| len.0 > index);
+
+        TestResult::from_bool(values(&left) == all[..index] && values(&right) == all[index..])
+    }
+
+    #[quickcheck]
+    fn persistent_updates_leaves_the_old_tree_alone(xs: Vec<u32>, x: u32) -> bool {
+        let tree = seq(&xs);
+        let _ = tree.push_front(Item(x)).push_back(Item(x));
+        let _ = tree.split(|len| len.0 > xs.len() / 2);
+
+        values(&tree) == xs
+    }
+
+    #[test]
+    fn large_tree_splits_everywhere() {
+        let xs = (0..500).collect::<Vec<_>>();
+        let tree = seq(&xs);
+
+        for index in 0..=xs.len() {
+            let (left, right) = tree.split(|len| len.0 > index);
+            assert_eq!(Sum(index), left.measure());
+            assert_eq!(values(&right), xs[index..]);
+        }
+    }
+
+    #[test]
+    fn max_measure_is_a_priority_queue() {
+        let mut queue = [5, 1, 9, 3, 9, 7]
+            .into_iter()
+            .map(Max)
+            .collect::<FingerTree<Max<u32>, Max<u32>>>();
+
+        let mut sorted = Vec::new();
+        while !queue.is_empty() {
+            let highest = queue.measure();
+            let (left, right) = queue.split(|max| *max >= highest);
+            let (top, right) = right.pop_front().unwrap();
+
+            sorted.push(top.0);
+            queue = left.concat(&right);
+        }
+
+        assert_eq!(vec![9, 9, 7, 5, 3, 1], sorted);
+    }
+
+    #[test]
+    fn combine_is_concat() {
+        let tree = seq(&[1, 2]).combine(seq(&[3]));
+
+        assert_eq!(seq(&[1, 2, 3]), tree);
+    }
+}
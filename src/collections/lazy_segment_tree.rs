@@ -0,0 +1,331 @@
+use std::ops::RangeBounds;
+
+use crate::action::MonoidAction;
+use crate::monoid::Monoid;
+
+use super::bounds;
+
+/// A segment tree that also updates whole ranges in O(log n) by keeping pending updates at the nodes that cover
+/// the range and only pushing them down when a node below it is touched.
+///
+/// The values are a [Monoid] `M` and the updates a monoid `A` that acts on it through [MonoidAction]. The action
+/// has to distribute over `M`'s combine, which is why a range sum is kept as a `(Sum<T>, Sum<usize>)` pair that
+/// knows how many values it covers.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::LazySegmentTree;
+///
+/// let mut tree = [4, 2, 7, 5].into_iter().map(Min).collect::<LazySegmentTree<_, Sum<i32>>>();
+///
+/// tree.apply(1..3, Sum(10));
+/// assert_eq!(Min(4), tree.query(..));
+/// assert_eq!(Min(12), tree.query(1..3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LazySegmentTree<M, A> {
+    len: usize,
+    tree: Vec<M>,
+    lazy: Vec<A>,
+}
+
+impl<M, A> LazySegmentTree<M, A>
+where
+    M: Monoid + Clone,
+    A: MonoidAction<M> + Clone,
+{
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the combination of all elements in `range`, or [Monoid::empty] if the range is empty.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> M {
+        let (start, end) = bounds(range, self.len);
+        if start == end {
+            return M::empty();
+        }
+
+        self.query_node(1, 0, self.len, start, end)
+    }
+
+    /// Applies `update` to every element in `range`.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn apply<R: RangeBounds<usize>>(&mut self, range: R, update: A) {
+        let (start, end) = bounds(range, self.len);
+        if start == end {
+            return;
+        }
+
+        self.apply_range(1, 0, self.len, start, end, &update);
+    }
+
+    /// Replaces the element at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn update(&mut self, index: usize, value: M) {
+        assert!(
+            index < self.len,
+            "index {index} out of bounds for length {}",
+            self.len
+        );
+
+        let (mut node, mut lo, mut hi) = (1, 0, self.len);
+        let mut path = Vec::new();
+        while hi - lo > 1 {
+            self.push(node);
+            path.push(node);
+
+            let mid = lo + (hi - lo) / 2;
+            if index < mid {
+                (node, hi) = (2 * node, mid);
+            } else {
+                (node, lo) = (2 * node + 1, mid);
+            }
+        }
+
+        self.tree[node] = value;
+        for node in path.into_iter().rev() {
+            self.pull(node);
+        }
+    }
+
+    // Nodes covers `lo..hi` and only gets called when it overlaps `start..end`, so they never act on an empty
+    // aggregate.
+    fn query_node(&self, node: usize, lo: usize, hi: usize, start: usize, end: usize) -> M {
+        if start <= lo && hi <= end {
+            return self.tree[node].clone();
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let result = if end <= mid {
+            self.query_node(2 * node, lo, mid, start, end)
+        } else if mid <= start {
+            self.query_node(2 * node + 1, mid, hi, start, end)
+        } else {
+            self.query_node(2 * node, lo, mid, start, end)
+                .combine(self.query_node(2 * node + 1, mid, hi, start, end))
+        };
+
+        self.lazy[node].act(result)
+    }
+
+    fn apply_range(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        start: usize,
+        end: usize,
+        update: &A,
+    ) {
+        if end <= lo || hi <= start {
+            return;
+        }
+        if start <= lo && hi <= end {
+            self.apply_node(node, update);
+            return;
+        }
+
+        self.push(node);
+        let mid = lo + (hi - lo) / 2;
+        self.apply_range(2 * node, lo, mid, start, end, update);
+        self.apply_range(2 * node + 1, mid, hi, start, end, update);
+        self.pull(node);
+    }
+
+    fn apply_node(&mut self, node: usize, update: &A) {
+        let value = std::mem::replace(&mut self.tree[node], M::empty());
+        self.tree[node] = update.act(value);
+
+        let pending = std::mem::replace(&mut self.lazy[node], A::empty());
+        self.lazy[node] = pending.combine(update.clone());
+    }
+
+    fn push(&mut self, node: usize) {
+        let pending = std::mem::replace(&mut self.lazy[node], A::empty());
+        self.apply_node(2 * node, &pending);
+        self.apply_node(2 * node + 1, &pending);
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.tree[node] = self.tree[2 * node]
+            .clone()
+            .combine(self.tree[2 * node + 1].clone());
+    }
+
+    fn build(&mut self, node: usize, values: &mut [Option<M>]) {
+        if values.len() == 1 {
+            self.tree[node] = values[0].take().unwrap();
+            return;
+        }
+
+        let mid = values.len() / 2;
+        let (left, right) = values.split_at_mut(mid);
+        self.build(2 * node, left);
+        self.build(2 * node + 1, right);
+        self.pull(node);
+    }
+}
+
+impl<M, A> FromIterator<M> for LazySegmentTree<M, A>
+where
+    M: Monoid + Clone,
+    A: MonoidAction<M> + Clone,
+{
+    fn from_iter<I: IntoIterator<Item = M>>(iter: I) -> Self {
+        let mut values = iter.into_iter().map(Some).collect::<Vec<_>>();
+        let len = values.len();
+        let nodes = 4 * len.max(1);
+
+        let mut tree = Self {
+            len,
+            tree: vec![M::empty(); nodes],
+            lazy: vec![A::empty(); nodes],
+        };
+        if len > 0 {
+            tree.build(1, &mut values);
+        }
+
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{Last, Max, Min, Sum};
+
+    use quickcheck::{Arbitrary, Gen, TestResult};
+    use quickcheck_macros::quickcheck;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Apply(usize, usize, i16),
+        Query(usize, usize),
+    }
+
+    impl Arbitrary for Op {
+        fn arbitrary(g: &mut Gen) -> Self {
+            if bool::arbitrary(g) {
+                Op::Apply(usize::arbitrary(g), usize::arbitrary(g), i16::arbitrary(g))
+            } else {
+                Op::Query(usize::arbitrary(g), usize::arbitrary(g))
+            }
+        }
+    }
+
+    fn range(a: usize, b: usize, len: usize) -> (usize, usize) {
+        let (a, b) = (a % (len + 1), b % (len + 1));
+        (a.min(b), a.max(b))
+    }
+
+    // Runs the operations on both the tree and a plain vector, `model` applies an update to a single value of the
+    // vector and `lift` turns a value into the monoid that the tree aggregates.
+    fn agrees_with_model<M, A>(
+        values: Vec<i16>,
+        ops: Vec<Op>,
+        lift: impl Fn(i64) -> M,
+        update: impl Fn(i64) -> A,
+        model: impl Fn(i64, i64) -> i64,
+    ) -> TestResult
+    where
+        M: Monoid + Clone + PartialEq,
+        A: MonoidAction<M> + Clone,
+    {
+        if values.is_empty() {
+            return TestResult::discard();
+        }
+
+        let mut naive = values.into_iter().map(i64::from).collect::<Vec<_>>();
+        let mut tree = naive
+            .iter()
+            .copied()
+            .map(&lift)
+            .collect::<LazySegmentTree<M, A>>();
+        let len = naive.len();
+
+        for op in ops {
+            match op {
+                Op::Apply(a, b, x) => {
+                    let (start, end) = range(a, b, len);
+                    naive[start..end]
+                        .iter_mut()
+                        .for_each(|v| *v = model(*v, x as i64));
+                    tree.apply(start..end, update(x as i64));
+                }
+                Op::Query(a, b) => {
+                    let (start, end) = range(a, b, len);
+                    let expected = naive[start..end]
+                        .iter()
+                        .fold(M::empty(), |acc, &v| acc.combine(lift(v)));
+
+                    if tree.query(start..end) != expected {
+                        return TestResult::failed();
+                    }
+                }
+            }
+        }
+
+        TestResult::passed()
+    }
+
+    #[quickcheck]
+    fn range_add_range_min(values: Vec<i16>, ops: Vec<Op>) -> TestResult {
+        agrees_with_model(values, ops, Min, Sum, |v, x| v + x)
+    }
+
+    #[quickcheck]
+    fn range_add_range_max(values: Vec<i16>, ops: Vec<Op>) -> TestResult {
+        agrees_with_model(values, ops, Max, Sum, |v, x| v + x)
+    }
+
+    #[quickcheck]
+    fn range_add_range_sum(values: Vec<i16>, ops: Vec<Op>) -> TestResult {
+        agrees_with_model(values, ops, |v| (Sum(v), Sum(1)), Sum, |v, x| v + x)
+    }
+
+    #[quickcheck]
+    fn range_assign_range_sum(values: Vec<i16>, ops: Vec<Op>) -> TestResult {
+        agrees_with_model(values, ops, |v| (Sum(v), Sum(1)), Last::from, |_, x| x)
+    }
+
+    #[quickcheck]
+    fn range_assign_range_min(values: Vec<i16>, ops: Vec<Op>) -> TestResult {
+        agrees_with_model(values, ops, Min, Last::from, |_, x| x)
+    }
+
+    #[test]
+    fn update_pushes_pending_updates() {
+        let mut tree = [1, 2, 3, 4]
+            .into_iter()
+            .map(|x| (Sum(x), Sum(1)))
+            .collect::<LazySegmentTree<_, Last<i32>>>();
+
+        tree.apply(.., Last::from(5));
+        tree.update(2, (Sum(1), Sum(1)));
+
+        assert_eq!((Sum(16), Sum(4)), tree.query(..));
+        assert_eq!((Sum(6), Sum(2)), tree.query(1..3));
+    }
+
+    #[test]
+    fn empty_tree() {
+        let mut tree = LazySegmentTree::<Min<i32>, Sum<i32>>::from_iter(None);
+
+        tree.apply(.., Sum(5));
+        assert!(tree.is_empty());
+        assert_eq!(Min::<i32>::empty(), tree.query(..));
+    }
+}
@@ -0,0 +1,42 @@
+//! Data structures that keep [Monoid](crate::Monoid) aggregates of their elements up to date.
+
+mod agg_queue;
+mod fenwick;
+mod finger_tree;
+mod lazy_segment_tree;
+mod monoid_stack;
+mod rope;
+mod segment_tree;
+mod sparse_table;
+
+pub use self::{
+    agg_queue::{AggQueue, SlidingWindow},
+    fenwick::Fenwick,
+    finger_tree::{FingerTree, Iter as FingerTreeIter, Measured},
+    lazy_segment_tree::LazySegmentTree,
+    monoid_stack::MonoidStack,
+    rope::{Rope, TextMetrics},
+    segment_tree::SegmentTree,
+    sparse_table::SparseTable,
+};
+
+use std::ops::{Bound, RangeBounds};
+
+/// Turns a range into `start..end` indices, panicking if it doesn't fit in a collection of `len` elements.
+pub(crate) fn bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(start <= end, "range start {start} is after its end {end}");
+    assert!(end <= len, "range end {end} out of bounds for length {len}");
+
+    (start, end)
+}
@@ -0,0 +1,134 @@
+use crate::semigroup::Semigroup;
+
+/// A stack that keeps the combination of all of its elements, so that the aggregate is available in O(1) at any
+/// time. Every push caches the aggregate of everything below it, which means popping never has to recompute
+/// anything.
+///
+/// Elements are combined from the bottom of the stack to the top.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::MonoidStack;
+///
+/// let mut stack = MonoidStack::new();
+/// stack.push(Min(-2));
+/// stack.push(Min(0));
+/// stack.push(Min(-3));
+/// assert_eq!(Some(&Min(-3)), stack.aggregate());
+///
+/// stack.pop();
+/// assert_eq!(Some(&Min(-2)), stack.aggregate());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoidStack<S> {
+    stack: Vec<(S, S)>,
+    top_down: bool,
+}
+
+impl<S: Semigroup + Clone> MonoidStack<S> {
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::new(),
+            top_down: false,
+        }
+    }
+
+    /// A stack that combines its elements from the top to the bottom instead, used for the front half of an
+    /// [AggQueue](super::AggQueue) where the top is the oldest element.
+    pub(crate) fn top_down() -> Self {
+        Self {
+            stack: Vec::new(),
+            top_down: true,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn push(&mut self, value: S) {
+        let aggregate = match self.aggregate() {
+            Some(below) if self.top_down => value.clone().combine(below.clone()),
+            Some(below) => below.clone().combine(value.clone()),
+            None => value.clone(),
+        };
+
+        self.stack.push((value, aggregate));
+    }
+
+    pub fn pop(&mut self) -> Option<S> {
+        self.stack.pop().map(|(value, _)| value)
+    }
+
+    pub fn peek(&self) -> Option<&S> {
+        self.stack.last().map(|(value, _)| value)
+    }
+
+    /// Returns the combination of all elements on the stack, or None if it is empty.
+    pub fn aggregate(&self) -> Option<&S> {
+        self.stack.last().map(|(_, aggregate)| aggregate)
+    }
+
+    /// Iterates over the elements from the bottom of the stack to the top.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &S> + ExactSizeIterator {
+        self.stack.iter().map(|(value, _)| value)
+    }
+}
+
+impl<S: Semigroup + Clone> Default for MonoidStack<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Semigroup + Clone> Extend<S> for MonoidStack<S> {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<S: Semigroup + Clone> FromIterator<S> for MonoidStack<S> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn aggregate_is_the_same_as_a_fold(values: Vec<String>, pops: usize) -> bool {
+        let mut stack = values.iter().cloned().collect::<MonoidStack<_>>();
+        let mut values = values;
+
+        for _ in 0..pops % (values.len() + 1) {
+            if stack.pop() != values.pop() {
+                return false;
+            }
+        }
+
+        stack.aggregate().cloned() == values.into_iter().reduce(|acc, x| acc.combine(x))
+    }
+
+    #[test]
+    fn top_down_combines_from_the_top() {
+        let mut stack = MonoidStack::top_down();
+        stack.push(String::from("c"));
+        stack.push(String::from("b"));
+        stack.push(String::from("a"));
+
+        assert_eq!(Some(&String::from("abc")), stack.aggregate());
+    }
+}
@@ -0,0 +1,533 @@
+use std::fmt;
+use std::ops::RangeBounds;
+
+use crate::monoid::Sum;
+use crate::semigroup::Semigroup;
+
+use super::bounds;
+use super::finger_tree::{FingerTree, Measured};
+
+// Chunks are kept below this size so that edits only copy a small part of the text.
+const MAX_CHUNK: usize = 1024;
+
+/// The size of a piece of text in bytes, chars and newlines. Every count is a [Sum] so the metrics of two pieces
+/// of text combine into the metrics of the concatenated text.
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::TextMetrics;
+///
+/// let metrics = TextMetrics::of("häj\n").combine(TextMetrics::of("då"));
+/// assert_eq!(TextMetrics::of("häj\ndå"), metrics);
+/// assert_eq!(Sum(6), metrics.chars);
+/// assert_eq!(Sum(8), metrics.bytes);
+/// assert_eq!(Sum(1), metrics.newlines);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextMetrics {
+    pub bytes: Sum<usize>,
+    pub chars: Sum<usize>,
+    pub newlines: Sum<usize>,
+}
+
+impl TextMetrics {
+    pub fn of(text: &str) -> Self {
+        Self {
+            bytes: Sum(text.len()),
+            chars: Sum(text.chars().count()),
+            newlines: Sum(text.bytes().filter(|&b| b == b'\n').count()),
+        }
+    }
+}
+
+impl Semigroup for TextMetrics {
+    fn combine(self, rhs: Self) -> Self {
+        Self {
+            bytes: self.bytes.combine(rhs.bytes),
+            chars: self.chars.combine(rhs.chars),
+            newlines: self.newlines.combine(rhs.newlines),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Chunk {
+    text: String,
+    metrics: TextMetrics,
+}
+
+impl Chunk {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            metrics: TextMetrics::of(text),
+        }
+    }
+}
+
+impl Measured<TextMetrics> for Chunk {
+    fn measure(&self) -> TextMetrics {
+        self.metrics
+    }
+}
+
+/// A text buffer made of small chunks of text in a [FingerTree] measured by [TextMetrics].
+///
+/// Edits and lookups only touch the chunks around the position, which makes them O(log n) instead of the O(n) it
+/// would take with a [String]. Positions are byte offsets into the text, lines and columns both starts at zero and
+/// columns are counted in chars.
+///
+/// A rope is a [Semigroup] under concatenation just like a [String].
+///
+/// # Examples
+/// ```
+/// use partial_functional::collections::Rope;
+///
+/// let mut rope = Rope::from("fn main() {\n}\n");
+/// rope.insert(rope.line_to_byte(1), "    println!(\"hi\");\n");
+///
+/// assert_eq!("fn main() {\n    println!(\"hi\");\n}\n", rope.to_string());
+/// assert_eq!(4, rope.len_lines());
+/// assert_eq!((1, 4), rope.byte_to_line_col(16));
+///
+/// rope.delete(..3);
+/// assert_eq!("main() {", rope.line(0));
+/// ```
+#[derive(Clone, Default)]
+pub struct Rope {
+    tree: FingerTree<TextMetrics, Chunk>,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the metrics of the whole text.
+    pub fn metrics(&self) -> TextMetrics {
+        self.tree.measure()
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.metrics().bytes.0
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.metrics().chars.0
+    }
+
+    /// Returns the number of lines, which is one more than the number of newlines.
+    pub fn len_lines(&self) -> usize {
+        self.metrics().newlines.0 + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// Inserts `text` at the byte offset `at`.
+    ///
+    /// # Panics
+    /// If `at` is out of bounds or not on a char boundary.
+    pub fn insert(&mut self, at: usize, text: &str) {
+        let (left, right) = self.split_at(at);
+        self.tree = left.push_str(text).tree.concat(&right.tree);
+    }
+
+    /// Removes the text in the byte `range`.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds or doesn't start and end on char boundaries.
+    pub fn delete<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = bounds(range, self.len_bytes());
+        let (left, rest) = self.split_at(start);
+        let (_, right) = rest.split_at(end - start);
+
+        self.tree = left.tree.concat(&right.tree);
+    }
+
+    /// Inserts `text` at the start of `line`.
+    ///
+    /// # Panics
+    /// If `line` is out of bounds.
+    pub fn insert_line(&mut self, line: usize, text: &str) {
+        self.insert(self.line_to_byte(line), text);
+    }
+
+    /// Removes the lines in `range`, including their newlines. When the range ends at the last line, the newline
+    /// in front of it is removed instead.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn delete_lines<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = bounds(range, self.len_lines());
+        if start == end {
+            return;
+        }
+
+        let range = if end < self.len_lines() {
+            self.line_to_byte(start)..self.line_to_byte(end)
+        } else if start > 0 {
+            self.line_to_byte(start) - 1..self.len_bytes()
+        } else {
+            0..self.len_bytes()
+        };
+
+        self.delete(range);
+    }
+
+    /// Splits the rope in two at the byte offset `at`.
+    ///
+    /// # Panics
+    /// If `at` is out of bounds or not on a char boundary.
+    pub fn split_at(&self, at: usize) -> (Self, Self) {
+        assert!(
+            at <= self.len_bytes(),
+            "byte {at} out of bounds for length {}",
+            self.len_bytes()
+        );
+
+        let (left, right) = self.tree.split(|metrics| metrics.bytes.0 > at);
+        let Some((chunk, right)) = right.pop_front() else {
+            return (Self { tree: left }, Self { tree: right });
+        };
+
+        let (head, tail) = chunk.text.split_at(at - left.measure().bytes.0);
+        let left = if head.is_empty() {
+            left
+        } else {
+            left.push_back(Chunk::new(head))
+        };
+        let right = if tail.is_empty() {
+            right
+        } else {
+            right.push_front(Chunk::new(tail))
+        };
+
+        (Self { tree: left }, Self { tree: right })
+    }
+
+    /// Returns the byte offset where `line` starts.
+    ///
+    /// # Panics
+    /// If `line` is out of bounds.
+    pub fn line_to_byte(&self, line: usize) -> usize {
+        assert!(
+            line < self.len_lines(),
+            "line {line} out of bounds for {} lines",
+            self.len_lines()
+        );
+        if line == 0 {
+            return 0;
+        }
+
+        let (left, right) = self.tree.split(|metrics| metrics.newlines.0 >= line);
+        let before = left.measure();
+        let chunk = right.front().expect("the newline is in the right half");
+        let newline = chunk
+            .text
+            .bytes()
+            .enumerate()
+            .filter(|&(_, b)| b == b'\n')
+            .nth(line - before.newlines.0 - 1)
+            .map(|(i, _)| i)
+            .expect("the newline is in the first chunk");
+
+        before.bytes.0 + newline + 1
+    }
+
+    /// Returns the line and column of the byte offset `at`.
+    ///
+    /// # Panics
+    /// If `at` is out of bounds or not on a char boundary.
+    pub fn byte_to_line_col(&self, at: usize) -> (usize, usize) {
+        let (left, _) = self.split_at(at);
+        let line = left.metrics().newlines.0;
+
+        (
+            line,
+            self.byte_to_char(at) - self.byte_to_char(self.line_to_byte(line)),
+        )
+    }
+
+    /// Returns the byte offset of `col` on `line`.
+    ///
+    /// # Panics
+    /// If `line` is out of bounds or `col` is past the end of the line.
+    pub fn line_col_to_byte(&self, line: usize, col: usize) -> usize {
+        let len = self.line(line).chars().count();
+        assert!(
+            col <= len,
+            "column {col} is past the end of line {line} with {len} chars"
+        );
+
+        self.char_to_byte(self.byte_to_char(self.line_to_byte(line)) + col)
+    }
+
+    /// Returns the number of chars before the byte offset `at`.
+    ///
+    /// # Panics
+    /// If `at` is out of bounds or not on a char boundary.
+    pub fn byte_to_char(&self, at: usize) -> usize {
+        self.split_at(at).0.len_chars()
+    }
+
+    /// Returns the byte offset of the char at index `at`.
+    ///
+    /// # Panics
+    /// If `at` is out of bounds.
+    pub fn char_to_byte(&self, at: usize) -> usize {
+        assert!(
+            at <= self.len_chars(),
+            "char {at} out of bounds for length {}",
+            self.len_chars()
+        );
+
+        let (left, right) = self.tree.split(|metrics| metrics.chars.0 > at);
+        let before = left.measure();
+        let within = right.front().map_or(0, |chunk| {
+            chunk
+                .text
+                .char_indices()
+                .nth(at - before.chars.0)
+                .map_or(chunk.text.len(), |(i, _)| i)
+        });
+
+        before.bytes.0 + within
+    }
+
+    /// Returns the text of `line` without its newline.
+    ///
+    /// # Panics
+    /// If `line` is out of bounds.
+    pub fn line(&self, line: usize) -> String {
+        let start = self.line_to_byte(line);
+        let end = if line + 1 == self.len_lines() {
+            self.len_bytes()
+        } else {
+            self.line_to_byte(line + 1) - 1
+        };
+
+        let (_, rest) = self.split_at(start);
+        rest.split_at(end - start).0.to_string()
+    }
+
+    /// Iterates over the chunks of text that makes up the rope.
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.tree.iter().map(|chunk| chunk.text.as_str())
+    }
+
+    // Appends text, topping up the last chunk before starting new ones.
+    fn push_str(self, mut text: &str) -> Self {
+        let mut tree = self.tree;
+
+        if let Some((rest, last)) = tree.pop_back() {
+            let room = MAX_CHUNK.saturating_sub(last.text.len());
+            let take = floor_char_boundary(text, room.min(text.len()));
+            tree = rest.push_back(Chunk::new(&(last.text + &text[..take])));
+            text = &text[take..];
+        }
+
+        while !text.is_empty() {
+            let mut take = floor_char_boundary(text, MAX_CHUNK.min(text.len()));
+            if take == 0 {
+                take = text.chars().next().map_or(0, char::len_utf8);
+            }
+
+            tree = tree.push_back(Chunk::new(&text[..take]));
+            text = &text[take..];
+        }
+
+        Self { tree }
+    }
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+impl Semigroup for Rope {
+    fn combine(self, rhs: Self) -> Self {
+        Self {
+            tree: self.tree.concat(&rhs.tree),
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(value: &str) -> Self {
+        Self::new().push_str(value)
+    }
+}
+
+impl From<String> for Rope {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<&Rope> for String {
+    fn from(value: &Rope) -> Self {
+        value.chunks().collect()
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
+
+// Equal texts can be chunked differently, so ropes are compared byte by byte.
+impl PartialEq for Rope {
+    fn eq(&self, other: &Self) -> bool {
+        self.len_bytes() == other.len_bytes()
+            && self
+                .chunks()
+                .flat_map(str::bytes)
+                .eq(other.chunks().flat_map(str::bytes))
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len_bytes() == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::Monoid;
+
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    fn char_boundary(text: &str, at: usize) -> usize {
+        floor_char_boundary(text, at % (text.len() + 1))
+    }
+
+    #[quickcheck]
+    fn insert_is_the_same_as_for_string(text: String, insert: String, at: usize) -> bool {
+        let at = char_boundary(&text, at);
+        let mut rope = Rope::from(text.as_str());
+        let mut expected = text;
+
+        rope.insert(at, &insert);
+        expected.insert_str(at, &insert);
+
+        rope == expected.as_str() && rope.metrics() == TextMetrics::of(&expected)
+    }
+
+    #[quickcheck]
+    fn delete_is_the_same_as_for_string(text: String, a: usize, b: usize) -> bool {
+        let (a, b) = (char_boundary(&text, a), char_boundary(&text, b));
+        let mut rope = Rope::from(text.as_str());
+        let mut expected = text;
+
+        rope.delete(a.min(b)..a.max(b));
+        expected.replace_range(a.min(b)..a.max(b), "");
+
+        rope == expected.as_str()
+    }
+
+    #[quickcheck]
+    fn line_lookups_agrees_with_str_lines(lines: Vec<String>) -> bool {
+        let lines = lines
+            .into_iter()
+            .map(|line| line.replace('\n', ""))
+            .collect::<Vec<_>>();
+        let text = lines.join("\n");
+        let rope = Rope::from(text.as_str());
+
+        rope.len_lines() == lines.len().max(1)
+            && lines.iter().enumerate().all(|(i, line)| {
+                let start = rope.line_to_byte(i);
+                rope.line(i) == *line
+                    && rope.byte_to_line_col(start) == (i, 0)
+                    && rope.line_col_to_byte(i, line.chars().count()) == start + line.len()
+            })
+    }
+
+    #[quickcheck]
+    fn char_and_byte_offsets_round_trips(text: String, at: usize) -> TestResult {
+        let rope = Rope::from(text.as_str());
+        let chars = at % (text.chars().count() + 1);
+
+        TestResult::from_bool(rope.byte_to_char(rope.char_to_byte(chars)) == chars)
+    }
+
+    #[test]
+    fn large_text_is_split_in_chunks() {
+        let text = "a line of text, ".repeat(50) + "\n";
+        let text = text.repeat(20);
+        let mut rope = Rope::from(text.as_str());
+
+        assert!(rope.chunks().all(|chunk| chunk.len() <= MAX_CHUNK));
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(21, rope.len_lines());
+
+        rope.insert_line(10, "inserted\n");
+        assert_eq!("inserted", rope.line(10));
+        assert_eq!(text.lines().nth(10).unwrap(), rope.line(11));
+
+        rope.delete_lines(0..10);
+        assert_eq!("inserted", rope.line(0));
+        assert_eq!(12, rope.len_lines());
+    }
+
+    #[test]
+    fn delete_lines_at_the_end_removes_the_newline_in_front() {
+        let mut rope = Rope::from("a\nb\nc");
+        rope.delete_lines(2..);
+        assert_eq!(rope, "a\nb");
+        assert_eq!(2, rope.len_lines());
+
+        rope.delete_lines(0..);
+        assert_eq!(rope, "");
+
+        let mut rope = Rope::from("a\nb\n");
+        rope.delete_lines(1..);
+        assert_eq!(rope, "a");
+
+        let mut rope = Rope::from("a\nb");
+        rope.delete_lines(1..1);
+        assert_eq!(rope, "a\nb");
+    }
+
+    #[test]
+    fn combine_concatenates() {
+        let rope = Rope::from("hello ")
+            .combine("world".into())
+            .combine(Rope::empty());
+
+        assert_eq!(rope, "hello world");
+        assert_eq!(String::from("hello world"), String::from(&rope));
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_inside_a_char_panics() {
+        let mut rope = Rope::from("å");
+
+        rope.insert(1, "a");
+    }
+}
@@ -0,0 +1,373 @@
+use std::ops::RangeBounds;
+
+use crate::monoid::Monoid;
+
+use super::bounds;
+
+/// A segment tree keeps the aggregate of every power-of-two aligned block of elements, which makes it possible to
+/// fold any range of elements and to change single elements in O(log n) combines.
+///
+/// Elements are always combined from left to right, so the monoid doesn't have to be commutative.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::SegmentTree;
+///
+/// let mut tree = [5, 3, 8, 1, 9].into_iter().map(Min).collect::<SegmentTree<_>>();
+///
+/// assert_eq!(Min(3), tree.query(0..3));
+/// assert_eq!(Min(1), tree.query(..));
+///
+/// tree.update(3, Min(7));
+/// assert_eq!(Min(3), tree.query(..));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentTree<M> {
+    len: usize,
+    size: usize,
+    tree: Vec<M>,
+}
+
+impl<M: Monoid + Clone> SegmentTree<M> {
+    /// Creates a tree of `len` elements that are all [Monoid::empty].
+    pub fn new(len: usize) -> Self {
+        let size = len.next_power_of_two();
+
+        Self {
+            len,
+            size,
+            tree: vec![M::empty(); 2 * size],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn get(&self, index: usize) -> &M {
+        assert!(
+            index < self.len,
+            "index {index} out of bounds for length {}",
+            self.len
+        );
+        &self.tree[self.size + index]
+    }
+
+    /// Replaces the element at `index` and updates the aggregates above it.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn update(&mut self, index: usize, value: M) {
+        assert!(
+            index < self.len,
+            "index {index} out of bounds for length {}",
+            self.len
+        );
+
+        let mut node = self.size + index;
+        self.tree[node] = value;
+        while node > 1 {
+            node >>= 1;
+            self.pull(node);
+        }
+    }
+
+    /// Returns the combination of all elements in `range`, or [Monoid::empty] if the range is empty.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> M {
+        let (start, end) = bounds(range, self.len);
+        let (mut l, mut r) = (start + self.size, end + self.size);
+        let (mut left, mut right) = (M::empty(), M::empty());
+
+        while l < r {
+            if l & 1 == 1 {
+                left = left.combine(self.tree[l].clone());
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = self.tree[r].clone().combine(right);
+            }
+
+            l >>= 1;
+            r >>= 1;
+        }
+
+        left.combine(right)
+    }
+
+    /// Returns the largest `end` such that `pred(&self.query(start..end))` holds.
+    ///
+    /// The predicate has to hold for [Monoid::empty] and be monotone, once it is false for a range it has to be
+    /// false for all ranges extending it to the right. A predicate on the accumulated [Sum](crate::Sum) like
+    /// `|sum| sum.0 <= budget` finds how far we get from `start` on a budget.
+    ///
+    /// # Panics
+    /// If `start` is out of bounds or the predicate doesn't hold for [Monoid::empty].
+    pub fn max_right<P: FnMut(&M) -> bool>(&self, start: usize, mut pred: P) -> usize {
+        assert!(
+            start <= self.len,
+            "start {start} out of bounds for length {}",
+            self.len
+        );
+        assert!(
+            pred(&M::empty()),
+            "the predicate has to hold for the empty monoid"
+        );
+
+        if start == self.len {
+            return self.len;
+        }
+
+        let mut node = start + self.size;
+        let mut acc = M::empty();
+        loop {
+            while node & 1 == 0 {
+                node >>= 1;
+            }
+
+            let next = acc.clone().combine(self.tree[node].clone());
+            if !pred(&next) {
+                while node < self.size {
+                    node *= 2;
+                    let next = acc.clone().combine(self.tree[node].clone());
+                    if pred(&next) {
+                        acc = next;
+                        node += 1;
+                    }
+                }
+
+                return node - self.size;
+            }
+
+            acc = next;
+            node += 1;
+            if node.is_power_of_two() {
+                return self.len;
+            }
+        }
+    }
+
+    /// Returns the smallest `start` such that `pred(&self.query(start..end))` holds.
+    ///
+    /// This is the mirror of [SegmentTree::max_right], the predicate has to hold for [Monoid::empty] and once it is
+    /// false for a range it has to be false for all ranges extending it to the left.
+    ///
+    /// # Panics
+    /// If `end` is out of bounds or the predicate doesn't hold for [Monoid::empty].
+    pub fn min_left<P: FnMut(&M) -> bool>(&self, end: usize, mut pred: P) -> usize {
+        assert!(
+            end <= self.len,
+            "end {end} out of bounds for length {}",
+            self.len
+        );
+        assert!(
+            pred(&M::empty()),
+            "the predicate has to hold for the empty monoid"
+        );
+
+        if end == 0 {
+            return 0;
+        }
+
+        let mut node = end + self.size;
+        let mut acc = M::empty();
+        loop {
+            node -= 1;
+            while node > 1 && node & 1 == 1 {
+                node >>= 1;
+            }
+
+            let next = self.tree[node].clone().combine(acc.clone());
+            if !pred(&next) {
+                while node < self.size {
+                    node = 2 * node + 1;
+                    let next = self.tree[node].clone().combine(acc.clone());
+                    if pred(&next) {
+                        acc = next;
+                        node -= 1;
+                    }
+                }
+
+                return node + 1 - self.size;
+            }
+
+            acc = next;
+            if node.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.tree[node] = self.tree[2 * node]
+            .clone()
+            .combine(self.tree[2 * node + 1].clone());
+    }
+}
+
+impl<M: Monoid + Clone> FromIterator<M> for SegmentTree<M> {
+    fn from_iter<I: IntoIterator<Item = M>>(iter: I) -> Self {
+        let values = iter.into_iter().collect::<Vec<_>>();
+        let mut tree = Self::new(values.len());
+
+        for (node, value) in (tree.size..).zip(values) {
+            tree.tree[node] = value;
+        }
+        for node in (1..tree.size).rev() {
+            tree.pull(node);
+        }
+
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{All, Any, First, Last, Max, Min, Sum};
+
+    use paste::paste;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    fn naive<M: Monoid + Clone>(values: &[M]) -> M {
+        values
+            .iter()
+            .cloned()
+            .fold(M::empty(), |acc, x| acc.combine(x))
+    }
+
+    macro_rules! segment_tree_properties {
+        ( $(($name:ident, $t:ty)),* $(,)? ) => {
+            $(
+                paste! {
+                    #[quickcheck]
+                    fn [<query_is_the_same_as_a_fold_with_ $name>](values: Vec<$t>, a: usize, b: usize) -> TestResult {
+                        if values.is_empty() {
+                            return TestResult::discard();
+                        }
+
+                        let (a, b) = (a % (values.len() + 1), b % (values.len() + 1));
+                        let (start, end) = (a.min(b), a.max(b));
+                        let tree = values.iter().cloned().collect::<SegmentTree<_>>();
+
+                        TestResult::from_bool(tree.query(start..end) == naive(&values[start..end]))
+                    }
+
+                    #[quickcheck]
+                    fn [<update_is_the_same_as_a_fold_with_ $name>](mut values: Vec<$t>, updates: Vec<(usize, $t)>) -> TestResult {
+                        if values.is_empty() {
+                            return TestResult::discard();
+                        }
+
+                        let mut tree = values.iter().cloned().collect::<SegmentTree<_>>();
+                        for (index, value) in updates {
+                            let index = index % values.len();
+                            values[index] = value.clone();
+                            tree.update(index, value);
+                        }
+
+                        TestResult::from_bool(
+                            (0..=values.len()).all(|end| tree.query(..end) == naive(&values[..end]))
+                        )
+                    }
+                }
+            )*
+        };
+    }
+
+    segment_tree_properties!(
+        (sum, Sum<u32>),
+        (min, Min<u32>),
+        (max, Max<u32>),
+        (first, First<u32>),
+        (last, Last<u32>),
+        (any, Any),
+        (all, All),
+        (string, String),
+    );
+
+    #[quickcheck]
+    fn max_right_finds_the_longest_prefix_within_budget(
+        values: Vec<Sum<u32>>,
+        start: usize,
+        budget: u32,
+    ) -> TestResult {
+        if values.is_empty() {
+            return TestResult::discard();
+        }
+
+        let start = start % (values.len() + 1);
+        let tree = values.iter().copied().collect::<SegmentTree<_>>();
+        let expected = (start..=values.len())
+            .take_while(|&end| naive(&values[start..end]).0 <= budget)
+            .last()
+            .unwrap();
+
+        TestResult::from_bool(tree.max_right(start, |sum| sum.0 <= budget) == expected)
+    }
+
+    #[quickcheck]
+    fn min_left_finds_the_longest_suffix_within_budget(
+        values: Vec<Sum<u32>>,
+        end: usize,
+        budget: u32,
+    ) -> TestResult {
+        if values.is_empty() {
+            return TestResult::discard();
+        }
+
+        let end = end % (values.len() + 1);
+        let tree = values.iter().copied().collect::<SegmentTree<_>>();
+        let expected = (0..=end)
+            .rev()
+            .take_while(|&start| naive(&values[start..end]).0 <= budget)
+            .last()
+            .unwrap();
+
+        TestResult::from_bool(tree.min_left(end, |sum| sum.0 <= budget) == expected)
+    }
+
+    #[test]
+    fn max_right_with_min_finds_first_smaller_element() {
+        let tree = [5, 4, 6, 2, 7]
+            .into_iter()
+            .map(Min)
+            .collect::<SegmentTree<_>>();
+
+        assert_eq!(3, tree.max_right(0, |min| min.0 > 2));
+        assert_eq!(5, tree.max_right(4, |min| min.0 > 2));
+        assert_eq!(2, tree.min_left(3, |min| min.0 > 4));
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree = SegmentTree::<Sum<u32>>::new(0);
+
+        assert!(tree.is_empty());
+        assert_eq!(Sum(0), tree.query(..));
+        assert_eq!(0, tree.max_right(0, |_| true));
+        assert_eq!(0, tree.min_left(0, |_| true));
+    }
+
+    #[test]
+    #[should_panic]
+    fn query_out_of_bounds_panics() {
+        let tree = SegmentTree::<Sum<u32>>::new(3);
+
+        tree.query(1..4);
+    }
+}
@@ -0,0 +1,137 @@
+use std::ops::RangeBounds;
+
+use crate::semigroup::Idempotent;
+
+use super::bounds;
+
+/// A sparse table keeps the combination of every range whose length is a power of two, which takes O(n log n)
+/// combines to build. Any other range is then covered by two, possibly overlapping, of those ranges so that a query
+/// is a single combine.
+///
+/// Overlapping ranges would count elements twice for most semigroups, so the table only accepts [Idempotent] ones
+/// like [Min](crate::Min), [Max](crate::Max), [Any](crate::Any), [All](crate::All), [BitOr](crate::BitOr) or
+/// [Gcd](crate::Gcd). The table can't be changed once it is built.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::SparseTable;
+///
+/// let table = [5, 3, 8, 1, 9].into_iter().map(Min).collect::<SparseTable<_>>();
+///
+/// assert_eq!(Some(Min(3)), table.query(0..3));
+/// assert_eq!(Some(Min(1)), table.query(..));
+/// assert_eq!(None, table.query(2..2));
+/// ```
+///
+/// A [Sum](crate::Sum) is not idempotent and can't be used.
+/// ```compile_fail
+/// use partial_functional::prelude::*;
+/// use partial_functional::collections::SparseTable;
+///
+/// let table = [5, 3, 8].into_iter().map(Sum).collect::<SparseTable<_>>();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseTable<S> {
+    levels: Vec<Vec<S>>,
+}
+
+impl<S: Idempotent + Clone> SparseTable<S> {
+    pub fn len(&self) -> usize {
+        self.levels.first().map_or(0, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the combination of all elements in `range`, or None if the range is empty.
+    ///
+    /// # Panics
+    /// If `range` is out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Option<S> {
+        let (start, end) = bounds(range, self.len());
+        if start == end {
+            return None;
+        }
+
+        let level = (end - start).ilog2() as usize;
+        let left = self.levels[level][start].clone();
+        let right = self.levels[level][end - (1 << level)].clone();
+
+        Some(left.combine(right))
+    }
+}
+
+impl<S: Idempotent + Clone> FromIterator<S> for SparseTable<S> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut levels = vec![iter.into_iter().collect::<Vec<_>>()];
+        let len = levels[0].len();
+
+        // Level `k` holds the combination of `i..i + 2^k` at index `i`, made from two halves in level `k - 1`.
+        let mut width = 1;
+        while 2 * width <= len {
+            let previous = &levels[levels.len() - 1];
+            let level = (0..=len - 2 * width)
+                .map(|i| previous[i].clone().combine(previous[i + width].clone()))
+                .collect();
+
+            levels.push(level);
+            width *= 2;
+        }
+
+        Self { levels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{All, Any, BitOr, First, Gcd, Last, Max, Min, Monoid};
+
+    use paste::paste;
+    use quickcheck::TestResult;
+    use quickcheck_macros::quickcheck;
+
+    fn naive<M: Monoid + Clone>(values: &[M]) -> Option<M> {
+        values.iter().cloned().reduce(|acc, x| acc.combine(x))
+    }
+
+    macro_rules! sparse_table_properties {
+        ( $(($name:ident, $t:ty)),* $(,)? ) => {
+            $(
+                paste! {
+                    #[quickcheck]
+                    fn [<query_is_the_same_as_a_fold_with_ $name>](values: Vec<$t>) -> TestResult {
+                        let table = values.iter().cloned().collect::<SparseTable<_>>();
+                        let len = values.len();
+
+                        TestResult::from_bool((0..=len).all(|start| {
+                            (start..=len).all(|end| table.query(start..end) == naive(&values[start..end]))
+                        }))
+                    }
+                }
+            )*
+        };
+    }
+
+    sparse_table_properties!(
+        (min, Min<u32>),
+        (max, Max<u32>),
+        (any, Any),
+        (all, All),
+        (bit_or, BitOr<u32>),
+        (gcd, Gcd<u32>),
+        (first, First<u32>),
+        (last, Last<u32>),
+    );
+
+    #[test]
+    fn empty_table() {
+        let table = SparseTable::<Max<u32>>::from_iter(None);
+
+        assert!(table.is_empty());
+        assert_eq!(None, table.query(..));
+    }
+}
@@ -0,0 +1,728 @@
+//! Loads a [Partial] configuration from layers that each set some of its fields.
+//!
+//! The layers are, from the lowest to the highest priority, built-in defaults, any number of TOML or JSON files in
+//! the order they're given, environment variables with a prefix and a list of `key=value` overrides. Every layer is
+//! read into the partial type and the layers are combined with [Semigroup::combine], so a field that is set in a
+//! higher layer replaces the same field of the layers below it.
+//!
+//! Environment variables and overrides only hold strings, so a value is read as whatever its field expects. A string
+//! field gets the text as it is, even when it looks like `8080` or `null`, a number or a bool is parsed from it and
+//! anything else, like a list, is read as JSON, such as `["a", "b"]`. Nested fields are separated with `__` in
+//! environment variables, like `APP_SERVER__PORT`, and with `.` in overrides, like `server.port=8080`.
+//!
+//! # Examples
+//! ```
+//! use partial_functional::prelude::*;
+//! use partial_functional::config::ConfigLoader;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, PartialEq, Partial)]
+//! #[partial(derive(Deserialize), attr(serde(default)))]
+//! struct Config {
+//!     name: String,
+//!     #[partial(nested)]
+//!     server: Server,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Partial)]
+//! #[partial(derive(Deserialize), attr(serde(default)))]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let defaults = PartialServer { host: Some("localhost".to_owned()), port: Some(80) };
+//! let env = [("APP_SERVER__PORT".to_owned(), "8080".to_owned())];
+//!
+//! let config = ConfigLoader::<Config>::new()
+//!     .defaults(PartialConfig { server: defaults, ..PartialConfig::empty() })
+//!     .env("APP", env)
+//!     .overrides(["name=api"])
+//!     .load()
+//!     .unwrap();
+//!
+//! assert_eq!("api", config.name);
+//! assert_eq!(Server { host: "localhost".to_owned(), port: 8080 }, config.server);
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Unexpected, Visitor};
+use serde_json::Value;
+
+use crate::monoid::Monoid;
+use crate::partial::{MissingFields, Partial};
+use crate::semigroup::Semigroup;
+
+/// Collects the layers of a configuration and combines them into a `T`, see the [module](self) for the order of the
+/// layers.
+pub struct ConfigLoader<T: Partial> {
+    defaults: T::Partial,
+    files: Vec<PathBuf>,
+    env: Vec<(String, String)>,
+    /// The names of the variables whose values aren't valid unicode.
+    env_not_unicode: Vec<String>,
+    env_prefix: Option<String>,
+    overrides: Vec<String>,
+}
+
+impl<T> ConfigLoader<T>
+where
+    T: Partial,
+    T::Partial: DeserializeOwned,
+{
+    pub fn new() -> Self {
+        Self {
+            defaults: T::Partial::empty(),
+            files: Vec::new(),
+            env: Vec::new(),
+            env_not_unicode: Vec::new(),
+            env_prefix: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Adds built-in defaults, which are combined with any defaults that were added before.
+    pub fn defaults(mut self, defaults: impl Into<T::Partial>) -> Self {
+        self.defaults =
+            std::mem::replace(&mut self.defaults, T::Partial::empty()).combine(defaults.into());
+        self
+    }
+
+    /// Adds a file that is read as TOML or JSON depending on its extension, a file that is added later has a higher
+    /// priority.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push(path.into());
+        self
+    }
+
+    /// Reads the variables that start with `prefix` and an underscore from `vars`, like `APP_PORT` for the prefix
+    /// `APP`. The names are lowercased and `__` separates a nested field.
+    pub fn env(mut self, prefix: &str, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env_prefix = Some(format!("{}_", prefix));
+        self.env = vars.into_iter().collect();
+        self.env_not_unicode.clear();
+        self
+    }
+
+    /// Reads the variables that start with `prefix` from the environment of the process, see [ConfigLoader::env].
+    ///
+    /// Variables with a name that isn't valid unicode are left out, and one with the prefix whose value isn't valid
+    /// unicode fails the load with [ConfigError::NotUnicode].
+    pub fn process_env(self, prefix: &str) -> Self {
+        self.env_os(prefix, std::env::vars_os())
+    }
+
+    fn env_os(self, prefix: &str, vars: impl IntoIterator<Item = (OsString, OsString)>) -> Self {
+        let mut valid = Vec::new();
+        let mut not_unicode = Vec::new();
+        for (name, value) in vars {
+            let Ok(name) = name.into_string() else {
+                continue;
+            };
+            match value.into_string() {
+                Ok(value) => valid.push((name, value)),
+                Err(_) => not_unicode.push(name),
+            }
+        }
+
+        let mut loader = self.env(prefix, valid);
+        loader.env_not_unicode = not_unicode;
+        loader
+    }
+
+    /// Adds overrides like `server.port=8080`, which have the highest priority.
+    pub fn overrides<S: Into<String>>(mut self, overrides: impl IntoIterator<Item = S>) -> Self {
+        self.overrides.extend(overrides.into_iter().map(Into::into));
+        self
+    }
+
+    /// Combines every layer without building the whole value.
+    pub fn load_partial(self) -> Result<T::Partial, ConfigError> {
+        let mut layers = vec![self.defaults];
+
+        for path in &self.files {
+            let value = read_file(path)?;
+            layers.push(deserialize(value, Source::File(path.clone()))?);
+        }
+
+        if let Some(prefix) = &self.env_prefix {
+            if let Some(name) = self
+                .env_not_unicode
+                .iter()
+                .find(|name| name.starts_with(prefix.as_str()))
+            {
+                return Err(ConfigError::NotUnicode(name.clone()));
+            }
+
+            let mut root = BTreeMap::new();
+            for (name, raw) in &self.env {
+                let Some(name) = name.strip_prefix(prefix.as_str()) else {
+                    continue;
+                };
+                let keys = name.split("__").map(str::to_lowercase).collect::<Vec<_>>();
+                insert(&mut root, &keys, raw.clone());
+            }
+            layers.push(deserialize(Node::Table(root), Source::Env)?);
+        }
+
+        let mut root = BTreeMap::new();
+        for item in &self.overrides {
+            let Some((key, raw)) = item.split_once('=') else {
+                return Err(ConfigError::InvalidOverride(item.clone()));
+            };
+            let keys = key.trim().split('.').map(str::to_owned).collect::<Vec<_>>();
+            insert(&mut root, &keys, raw.trim().to_owned());
+        }
+        layers.push(deserialize(Node::Table(root), Source::Overrides)?);
+
+        Ok(layers
+            .into_iter()
+            .fold(T::Partial::empty(), Semigroup::combine))
+    }
+
+    /// Combines every layer and builds the whole value, or fails with every field that none of the layers set.
+    pub fn load(self) -> Result<T, ConfigError> {
+        T::from_partial(self.load_partial()?).map_err(ConfigError::Missing)
+    }
+}
+
+impl<T> Default for ConfigLoader<T>
+where
+    T: Partial,
+    T::Partial: DeserializeOwned,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_file(path: &Path) -> Result<Value, ConfigError> {
+    let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    let invalid = |message: String| ConfigError::Invalid {
+        source: Source::File(path.to_owned()),
+        message,
+    };
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|err| invalid(err.to_string())),
+        Some("json") => serde_json::from_str(&contents).map_err(|err| invalid(err.to_string())),
+        _ => Err(ConfigError::UnknownFormat(path.to_owned())),
+    }
+}
+
+fn deserialize<'de, P, D>(layer: D, source: Source) -> Result<P, ConfigError>
+where
+    P: DeserializeOwned,
+    D: Deserializer<'de, Error = serde_json::Error>,
+{
+    P::deserialize(layer).map_err(|err| ConfigError::Invalid {
+        source,
+        message: err.to_string(),
+    })
+}
+
+/// Puts `raw` at the path of `keys` in `root`, making tables along the way.
+fn insert(root: &mut BTreeMap<String, Node>, keys: &[String], raw: String) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+
+    let mut current = root;
+    for key in parents {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Node::Table(BTreeMap::new()));
+        if let Node::Raw(_) = entry {
+            *entry = Node::Table(BTreeMap::new());
+        }
+        let Node::Table(table) = entry else {
+            unreachable!()
+        };
+        current = table;
+    }
+
+    current.insert(last.clone(), Node::Raw(raw));
+}
+
+/// The values of the environment variables or the overrides, with a table for every level of nested fields.
+///
+/// A value is read as whatever the field it ends up in asks for, instead of guessing its type up front, so that a
+/// string field named `8080` stays a string.
+enum Node {
+    Raw(String),
+    Table(BTreeMap<String, Node>),
+}
+
+/// Reads `raw` as JSON, or as a string when it isn't JSON or is `null`, so that a value never unsets a field.
+fn json(raw: String) -> Value {
+    match serde_json::from_str(&raw) {
+        Ok(Value::Null) | Err(_) => Value::String(raw),
+        Ok(value) => value,
+    }
+}
+
+macro_rules! parse_raw {
+    ( $( $method:ident => $visit:ident ),* $(,)? ) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let raw = match self {
+                    Node::Raw(raw) => raw,
+                    table => return table.deserialize_any(visitor),
+                };
+
+                match raw.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&raw), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! through_json {
+    ( $( $method:ident ( $( $arg:ident: $ty:ty ),* ) ),* $(,)? ) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $( $arg: $ty, )* visitor: V) -> Result<V::Value, Self::Error> {
+                match self {
+                    Node::Raw(raw) => json(raw).$method($( $arg, )* visitor),
+                    table => table.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Node {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Raw(raw) => json(raw).deserialize_any(visitor),
+            Node::Table(table) => {
+                let mut map = MapDeserializer::new(table.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Node::Raw(raw) => visitor.visit_string(raw),
+            table => table.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    parse_raw!(
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    );
+
+    through_json!(
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_ignored_any(),
+    );
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for Node {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Where a layer of the configuration came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Env,
+    Overrides,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env => write!(f, "the environment"),
+            Source::Overrides => write!(f, "the overrides"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A file couldn't be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A file has an extension other than `toml` or `json`.
+    UnknownFormat(PathBuf),
+    /// An override doesn't look like `key=value`.
+    InvalidOverride(String),
+    /// The value of this environment variable isn't valid unicode.
+    NotUnicode(String),
+    /// A layer couldn't be parsed or doesn't fit the partial type.
+    Invalid { source: Source, message: String },
+    /// None of the layers set these fields.
+    Missing(MissingFields),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "couldn't read {}: {}", path.display(), source)
+            }
+            ConfigError::UnknownFormat(path) => {
+                write!(
+                    f,
+                    "{} should have a `toml` or `json` extension",
+                    path.display()
+                )
+            }
+            ConfigError::InvalidOverride(item) => {
+                write!(f, "the override `{}` should look like `key=value`", item)
+            }
+            ConfigError::NotUnicode(name) => {
+                write!(f, "the value of ${} isn't valid unicode", name)
+            }
+            ConfigError::Invalid { source, message } => {
+                write!(f, "invalid configuration in {}: {}", source, message)
+            }
+            ConfigError::Missing(missing) => write!(f, "incomplete configuration, {}", missing),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Missing(missing) => Some(missing),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serde::Deserialize;
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::Partial;
+
+    #[derive(Debug, PartialEq, Partial)]
+    #[partial(derive(Debug, PartialEq, Deserialize), attr(serde(default)))]
+    struct Config {
+        name: String,
+        #[partial(default = 4)]
+        workers: u8,
+        tags: Option<Vec<String>>,
+        #[partial(nested)]
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Partial)]
+    #[partial(derive(Debug, PartialEq, Deserialize), attr(serde(default)))]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    fn file(extension: &str, contents: &str) -> NamedTempFile {
+        let mut file = tempfile::Builder::new()
+            .suffix(extension)
+            .tempfile()
+            .unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn defaults() -> PartialConfig {
+        PartialConfig {
+            name: Some("app".to_owned()),
+            server: PartialServer {
+                host: Some("localhost".to_owned()),
+                port: Some(80),
+            },
+            ..PartialConfig::empty()
+        }
+    }
+
+    #[test]
+    fn later_layers_have_a_higher_priority() {
+        let toml = file(
+            ".toml",
+            "name = \"from-toml\"\nworkers = 2\n\n[server]\nport = 8000\n",
+        );
+        let json = file(
+            ".json",
+            r#"{ "workers": 6, "server": { "host": "example.org" } }"#,
+        );
+
+        let config = ConfigLoader::<Config>::new()
+            .defaults(defaults())
+            .file(toml.path())
+            .file(json.path())
+            .env(
+                "APP",
+                env(&[("APP_SERVER__PORT", "9000"), ("APP_WORKERS", "8")]),
+            )
+            .overrides(["server.port=9999", "tags=[\"a\", \"b\"]"])
+            .load()
+            .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                name: "from-toml".to_owned(),
+                workers: 8,
+                tags: Some(vec!["a".to_owned(), "b".to_owned()]),
+                server: Server {
+                    host: "example.org".to_owned(),
+                    port: 9999,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn the_order_of_the_calls_doesnt_change_the_priority() {
+        let config = ConfigLoader::<Config>::new()
+            .overrides(["name=override"])
+            .env("APP", env(&[("APP_NAME", "env")]))
+            .defaults(defaults())
+            .load()
+            .unwrap();
+
+        assert_eq!("override", config.name);
+    }
+
+    #[test]
+    fn env_only_reads_the_prefix() {
+        let vars = env(&[
+            ("APP_NAME", "mine"),
+            ("OTHER_NAME", "theirs"),
+            ("APPLICATION_NAME", "theirs"),
+            ("APP_UNKNOWN", "ignored"),
+        ]);
+
+        let partial = ConfigLoader::<Config>::new()
+            .env("APP", vars)
+            .load_partial()
+            .unwrap();
+
+        assert_eq!(Some("mine".to_owned()), partial.name);
+    }
+
+    #[test]
+    fn values_that_arent_json_are_strings() {
+        let partial = ConfigLoader::<Config>::new()
+            .overrides(["name = hello world", "server.host=127.0.0.1"])
+            .load_partial()
+            .unwrap();
+
+        assert_eq!(Some("hello world".to_owned()), partial.name);
+        assert_eq!(Some("127.0.0.1".to_owned()), partial.server.host);
+    }
+
+    #[test]
+    fn string_fields_keep_values_that_look_like_json() {
+        for value in ["8080", "true", "1.0", "null", "[1]"] {
+            let partial = ConfigLoader::<Config>::new()
+                .env(
+                    "APP",
+                    env(&[("APP_NAME", value), ("APP_SERVER__HOST", value)]),
+                )
+                .overrides([format!("tags=[\"{}\"]", value)])
+                .load_partial()
+                .unwrap();
+
+            assert_eq!(Some(value.to_owned()), partial.name);
+            assert_eq!(Some(value.to_owned()), partial.server.host);
+            assert_eq!(Some(vec![value.to_owned()]), partial.tags);
+        }
+    }
+
+    #[test]
+    fn null_doesnt_unset_a_field() {
+        let err = ConfigLoader::<Config>::new()
+            .defaults(defaults())
+            .overrides(["server.port=null"])
+            .load()
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                source: Source::Overrides,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn env_values_have_to_be_unicode() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let not_unicode = || OsString::from_vec(vec![0x66, 0x6f, 0x80]);
+        let vars = |name: OsString| vec![(name, not_unicode()), ("APP_NAME".into(), "app".into())];
+
+        let partial = ConfigLoader::<Config>::new()
+            .env_os("APP", vars("OTHER".into()))
+            .env_os("APP", vars(not_unicode()))
+            .load_partial()
+            .unwrap();
+        assert_eq!(Some("app".to_owned()), partial.name);
+
+        let err = ConfigLoader::<Config>::new()
+            .env_os("APP", vars("APP_SERVER__HOST".into()))
+            .load_partial()
+            .unwrap_err();
+        assert!(matches!(&err, ConfigError::NotUnicode(name) if name == "APP_SERVER__HOST"));
+        assert_eq!(
+            "the value of $APP_SERVER__HOST isn't valid unicode",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn defaults_fill_in_the_rest() {
+        let config = ConfigLoader::<Config>::new()
+            .defaults(defaults())
+            .load()
+            .unwrap();
+
+        assert_eq!(4, config.workers);
+        assert_eq!(None, config.tags);
+    }
+
+    #[test]
+    fn every_missing_field_is_reported() {
+        let err = ConfigLoader::<Config>::new()
+            .env("APP", env(&[("APP_SERVER__PORT", "1")]))
+            .load()
+            .unwrap_err();
+
+        match err {
+            ConfigError::Missing(missing) => assert_eq!(missing.paths(), ["name", "server.host"]),
+            err => panic!("expected missing fields, got {}", err),
+        }
+    }
+
+    #[test]
+    fn errors_name_the_layer() {
+        let broken = file(".toml", "name = ");
+        let err = ConfigLoader::<Config>::new()
+            .file(broken.path())
+            .load()
+            .unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::Invalid { source: Source::File(path), .. } if path == broken.path())
+        );
+
+        let err = ConfigLoader::<Config>::new()
+            .env("APP", env(&[("APP_WORKERS", "many")]))
+            .load()
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            ConfigError::Invalid {
+                source: Source::Env,
+                ..
+            }
+        ));
+        assert!(err
+            .to_string()
+            .starts_with("invalid configuration in the environment"));
+
+        let err = ConfigLoader::<Config>::new()
+            .overrides(["workers"])
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidOverride(item) if item == "workers"));
+    }
+
+    #[test]
+    fn files_need_a_known_format_and_have_to_exist() {
+        let yaml = file(".yaml", "name: app");
+        let err = ConfigLoader::<Config>::new()
+            .file(yaml.path())
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::UnknownFormat(_)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let err = ConfigLoader::<Config>::new()
+            .file(dir.path().join("missing.toml"))
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::Io { .. }));
+        assert!(err.source().is_some());
+    }
+}
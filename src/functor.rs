@@ -16,6 +16,37 @@ impl<A, B, E> Functor<A, B> for Result<A, E> {
     }
 }
 
+/// A [Functor] that can hold any number of values, like a `Vec`, so the function has to be callable more than once.
+///
+/// [Option] and [Result] implement both traits, `fmap_mut` is the same as `fmap` for them.
+/// ```
+/// use partial_functional::functor::FunctorMut;
+///
+/// assert_eq!(vec![2, 4, 6], vec![1, 2, 3].fmap_mut(|x| x * 2));
+/// assert_eq!(Some("1".to_owned()), Some(1).fmap_mut(|x| x.to_string()));
+/// ```
+pub trait FunctorMut<A, B>: HKT<A, B> {
+    fn fmap_mut<F: FnMut(A) -> B>(self, f: F) -> <Self as HKT<A, B>>::Target;
+}
+
+impl<A, B> FunctorMut<A, B> for Option<A> {
+    fn fmap_mut<F: FnMut(A) -> B>(self, f: F) -> <Self as HKT<A, B>>::Target {
+        self.map(f)
+    }
+}
+
+impl<A, B, E> FunctorMut<A, B> for Result<A, E> {
+    fn fmap_mut<F: FnMut(A) -> B>(self, f: F) -> <Self as HKT<A, B>>::Target {
+        self.map(f)
+    }
+}
+
+impl<A, B> FunctorMut<A, B> for Vec<A> {
+    fn fmap_mut<F: FnMut(A) -> B>(self, f: F) -> <Self as HKT<A, B>>::Target {
+        self.into_iter().map(f).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::identity;
@@ -42,6 +73,11 @@ mod tests {
 
     functor_identity!((option, Option<u32>), (result, Result<u32, u8>),);
 
+    #[quickcheck]
+    fn functor_identity_law_with_vec(value: Vec<u32>) -> bool {
+        value.clone().fmap_mut(identity) == identity(value)
+    }
+
     macro_rules! assert_composition {
         ( $name:ident, $f:expr, $g:expr ) => {
             let f = $f;
@@ -83,4 +119,31 @@ mod tests {
             |x| x * 2
         }
     }
+
+    #[quickcheck]
+    fn test_functor_composition_law_with_vec(value: Vec<u16>) -> bool {
+        let value = value.into_iter().map(u32::from).collect::<Vec<_>>();
+
+        value.clone().fmap_mut(|x| (x + 2) * 2) == value.fmap_mut(|x| x + 2).fmap_mut(|x| x * 2)
+    }
+
+    #[test]
+    fn fmap_takes_a_function_that_can_only_be_called_once() {
+        let name = String::from("id");
+        let labelled = Some(1).fmap(move |x| (name, x));
+
+        assert_eq!(labelled, Some((String::from("id"), 1)));
+    }
+
+    #[test]
+    fn vec_fmap_mut_calls_the_function_for_every_element_in_order() {
+        let mut seen = Vec::new();
+        let lengths = vec!["a", "bb", "ccc"].fmap_mut(|s| {
+            seen.push(s);
+            s.len()
+        });
+
+        assert_eq!(lengths, vec![1, 2, 3]);
+        assert_eq!(seen, vec!["a", "bb", "ccc"]);
+    }
 }
@@ -0,0 +1,75 @@
+use crate::monoid::{Monoid, Sum, Xor};
+
+/// A group is a [Monoid] where every value has an inverse that combines with it into [Monoid::empty].
+///
+/// `x.clone().combine(x.invert()) == Self::empty()`
+///
+/// Inverses are what lets us undo a combine, so that a range can be computed from two prefixes like in the
+/// [Fenwick](crate::collections::Fenwick) tree.
+/// ```
+/// use partial_functional::prelude::*;
+///
+/// assert_eq!(Sum(-5), Sum(5).invert());
+/// assert_eq!(Sum::<i32>::empty(), Sum(5).combine(Sum(5).invert()));
+/// assert_eq!(Xor(0b1010), Xor(0b1010).invert());
+/// ```
+pub trait Group: Monoid {
+    fn invert(self) -> Self;
+}
+
+impl<T> Group for Sum<T>
+where
+    Sum<T>: Monoid,
+    T: std::ops::Neg<Output = T>,
+{
+    fn invert(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+/// Every value is its own inverse under exclusive or.
+impl<T> Group for Xor<T>
+where
+    Xor<T>: Monoid,
+{
+    fn invert(self) -> Self {
+        self
+    }
+}
+
+macro_rules! impl_group_with_negation {
+    ( $($x:ty),* ) => {
+        $(
+            impl Group for $x {
+                fn invert(self) -> Self {
+                    -self
+                }
+            }
+        )*
+    };
+}
+
+impl_group_with_negation!(isize, i8, i16, i32, i64, i128, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::semigroup::Semigroup;
+
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn sum_inverse_property(x: i32) -> bool {
+        let x = Sum(x as i64);
+
+        x.combine(x.invert()) == Sum::empty() && x.invert().combine(x) == Sum::empty()
+    }
+
+    #[quickcheck]
+    fn xor_inverse_property(x: u32) -> bool {
+        let x = Xor(x);
+
+        x.combine(x.invert()) == Xor::empty()
+    }
+}
@@ -12,3 +12,8 @@ impl<A, B, E> HKT<A, B> for Result<A, E> {
     type URI = Self;
     type Target = Result<B, E>;
 }
+
+impl<A, B> HKT<A, B> for Vec<A> {
+    type URI = Self;
+    type Target = Vec<B>;
+}
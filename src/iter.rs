@@ -0,0 +1,481 @@
+//! Iterator adapters that combine the elements of an iterator as they go.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::mem;
+
+use crate::monoid::Monoid;
+use crate::semigroup::Semigroup;
+
+/// Extension methods for any [Iterator] over [Semigroup]s or [Monoid]s.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+///
+/// let totals = [3, 1, 4].into_iter().map(Sum).running_combine().collect::<Vec<_>>();
+/// assert_eq!(vec![Sum(3), Sum(4), Sum(8)], totals);
+/// ```
+pub trait CombineIterator: Iterator {
+    /// Yields the combination of every element seen so far, including the current one.
+    fn running_combine(self) -> RunningCombine<Self>
+    where
+        Self: Sized,
+        Self::Item: Semigroup + Clone,
+    {
+        RunningCombine {
+            iter: self,
+            acc: None,
+        }
+    }
+
+    /// Yields the combination of every element before the current one, starting with [Monoid::empty].
+    ///
+    /// The combination of all the elements is never yielded, so the adapter yields exactly as many items as the
+    /// underlying iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use partial_functional::prelude::*;
+    ///
+    /// let offsets = [3, 1, 4].into_iter().map(Sum).running_combine_exclusive().collect::<Vec<_>>();
+    /// assert_eq!(vec![Sum(0), Sum(3), Sum(4)], offsets);
+    /// ```
+    fn running_combine_exclusive(self) -> RunningCombineExclusive<Self>
+    where
+        Self: Sized,
+        Self::Item: Monoid + Clone,
+    {
+        RunningCombineExclusive {
+            iter: self,
+            acc: Self::Item::empty(),
+        }
+    }
+
+    /// Yields the combination of every element from the current one to the end, starting at the back.
+    ///
+    /// The elements keep their order when combined, so the first item yielded is the last element and the final item
+    /// is the combination of all of them.
+    ///
+    /// # Examples
+    /// ```
+    /// use partial_functional::prelude::*;
+    ///
+    /// let suffixes = ["a", "b", "c"].map(String::from).into_iter().suffix_combine().collect::<Vec<_>>();
+    /// assert_eq!(vec!["c", "bc", "abc"], suffixes);
+    /// ```
+    fn suffix_combine(self) -> SuffixCombine<Self>
+    where
+        Self: Sized + DoubleEndedIterator,
+        Self::Item: Semigroup + Clone,
+    {
+        SuffixCombine {
+            iter: self,
+            acc: None,
+        }
+    }
+
+    /// Groups the elements by `key_fn` and combines the result of `value_fn` for every element in a group, in the
+    /// order the elements come in.
+    ///
+    /// # Examples
+    /// ```
+    /// use partial_functional::prelude::*;
+    ///
+    /// let rows = [("apples", 3), ("pears", 2), ("apples", 4)];
+    /// let totals = rows.into_iter().group_combine(|(fruit, _)| *fruit, |(_, count)| Sum(*count));
+    ///
+    /// assert_eq!(Some(&Sum(7)), totals.get("apples"));
+    /// assert_eq!(Some(&Sum(2)), totals.get("pears"));
+    /// ```
+    fn group_combine<K, M, FK, FV>(self, key_fn: FK, value_fn: FV) -> HashMap<K, M>
+    where
+        Self: Sized,
+        K: Hash + Eq,
+        M: Monoid,
+        FK: FnMut(&Self::Item) -> K,
+        FV: FnMut(&Self::Item) -> M,
+    {
+        let mut groups = HashMap::new();
+        for_each_group(self, key_fn, value_fn, |key, value| {
+            combine_into(groups.entry(key).or_insert_with(M::empty), value)
+        });
+
+        groups
+    }
+
+    /// The same as [CombineIterator::group_combine] but the groups are kept sorted by their key.
+    fn group_combine_ordered<K, M, FK, FV>(self, key_fn: FK, value_fn: FV) -> BTreeMap<K, M>
+    where
+        Self: Sized,
+        K: Ord,
+        M: Monoid,
+        FK: FnMut(&Self::Item) -> K,
+        FV: FnMut(&Self::Item) -> M,
+    {
+        let mut groups = BTreeMap::new();
+        for_each_group(self, key_fn, value_fn, |key, value| {
+            combine_into(groups.entry(key).or_insert_with(M::empty), value)
+        });
+
+        groups
+    }
+
+    /// Combines runs of consecutive elements with the same key, yielding each group as soon as it ends. For input that
+    /// is already sorted by the key this gives the same groups as [CombineIterator::group_combine] without having to
+    /// hold all of them in memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use partial_functional::prelude::*;
+    ///
+    /// let days = [(1, 20), (1, 25), (2, 18), (3, 30), (3, 22)];
+    /// let hottest = days
+    ///     .into_iter()
+    ///     .group_combine_sorted(|(day, _)| *day, |(_, temperature)| Max(*temperature))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec![(1, Max(25)), (2, Max(18)), (3, Max(30))], hottest);
+    /// ```
+    fn group_combine_sorted<K, M, FK, FV>(
+        self,
+        key_fn: FK,
+        value_fn: FV,
+    ) -> GroupCombineSorted<Self, K, M, FK, FV>
+    where
+        Self: Sized,
+        K: PartialEq,
+        M: Semigroup,
+        FK: FnMut(&Self::Item) -> K,
+        FV: FnMut(&Self::Item) -> M,
+    {
+        GroupCombineSorted {
+            iter: self,
+            key_fn,
+            value_fn,
+            pending: None,
+        }
+    }
+}
+
+fn for_each_group<I, K, M, FK, FV>(
+    iter: I,
+    mut key_fn: FK,
+    mut value_fn: FV,
+    mut add: impl FnMut(K, M),
+) where
+    I: Iterator,
+    FK: FnMut(&I::Item) -> K,
+    FV: FnMut(&I::Item) -> M,
+{
+    for item in iter {
+        add(key_fn(&item), value_fn(&item));
+    }
+}
+
+/// Combines `value` onto the accumulator behind `slot` without having to clone it.
+fn combine_into<M: Monoid>(slot: &mut M, value: M) {
+    let acc = mem::replace(slot, M::empty());
+    *slot = acc.combine(value);
+}
+
+impl<I: Iterator> CombineIterator for I {}
+
+/// Iterator returned by [CombineIterator::running_combine].
+#[derive(Debug, Clone)]
+pub struct RunningCombine<I: Iterator> {
+    iter: I,
+    acc: Option<I::Item>,
+}
+
+impl<I> Iterator for RunningCombine<I>
+where
+    I: Iterator,
+    I::Item: Semigroup + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.next()?;
+        let acc = match self.acc.take() {
+            Some(acc) => acc.combine(value),
+            None => value,
+        };
+
+        self.acc = Some(acc.clone());
+        Some(acc)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator returned by [CombineIterator::running_combine_exclusive].
+#[derive(Debug, Clone)]
+pub struct RunningCombineExclusive<I: Iterator> {
+    iter: I,
+    acc: I::Item,
+}
+
+impl<I> Iterator for RunningCombineExclusive<I>
+where
+    I: Iterator,
+    I::Item: Monoid + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.next()?;
+        let next = self.acc.clone().combine(value);
+
+        Some(mem::replace(&mut self.acc, next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator returned by [CombineIterator::suffix_combine].
+#[derive(Debug, Clone)]
+pub struct SuffixCombine<I: Iterator> {
+    iter: I,
+    acc: Option<I::Item>,
+}
+
+impl<I> Iterator for SuffixCombine<I>
+where
+    I: DoubleEndedIterator,
+    I::Item: Semigroup + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.next_back()?;
+        let acc = match self.acc.take() {
+            Some(acc) => value.combine(acc),
+            None => value,
+        };
+
+        self.acc = Some(acc.clone());
+        Some(acc)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator returned by [CombineIterator::group_combine_sorted].
+#[derive(Clone)]
+pub struct GroupCombineSorted<I, K, M, FK, FV> {
+    iter: I,
+    key_fn: FK,
+    value_fn: FV,
+    pending: Option<(K, M)>,
+}
+
+impl<I, K, M, FK, FV> Iterator for GroupCombineSorted<I, K, M, FK, FV>
+where
+    I: Iterator,
+    K: PartialEq,
+    M: Semigroup,
+    FK: FnMut(&I::Item) -> K,
+    FV: FnMut(&I::Item) -> M,
+{
+    type Item = (K, M);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, mut acc) = match self.pending.take() {
+            Some(group) => group,
+            None => {
+                let item = self.iter.next()?;
+                ((self.key_fn)(&item), (self.value_fn)(&item))
+            }
+        };
+
+        for item in self.iter.by_ref() {
+            let next_key = (self.key_fn)(&item);
+            let value = (self.value_fn)(&item);
+
+            if next_key == key {
+                acc = acc.combine(value);
+            } else {
+                self.pending = Some((next_key, value));
+                break;
+            }
+        }
+
+        Some((key, acc))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.pending.is_some() as usize;
+        let (low, high) = self.iter.size_hint();
+
+        (
+            (low + pending).min(1),
+            high.and_then(|high| high.checked_add(pending)),
+        )
+    }
+}
+
+impl<I, K, M, FK, FV> std::fmt::Debug for GroupCombineSorted<I, K, M, FK, FV>
+where
+    I: std::fmt::Debug,
+    K: std::fmt::Debug,
+    M: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupCombineSorted")
+            .field("iter", &self.iter)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+impl<I> ExactSizeIterator for RunningCombine<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Semigroup + Clone,
+{
+}
+
+impl<I> ExactSizeIterator for RunningCombineExclusive<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Monoid + Clone,
+{
+}
+
+impl<I> ExactSizeIterator for SuffixCombine<I>
+where
+    I: ExactSizeIterator + DoubleEndedIterator,
+    I::Item: Semigroup + Clone,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{Max, Sum};
+
+    use quickcheck_macros::quickcheck;
+
+    fn naive<S: Semigroup + Clone>(values: &[S]) -> Option<S> {
+        values.iter().cloned().reduce(|acc, x| acc.combine(x))
+    }
+
+    #[quickcheck]
+    fn running_combine_yields_every_prefix(values: Vec<String>) -> bool {
+        let running = values.iter().cloned().running_combine().collect::<Vec<_>>();
+
+        running.len() == values.len()
+            && running
+                .iter()
+                .enumerate()
+                .all(|(i, acc)| Some(acc) == naive(&values[..=i]).as_ref())
+    }
+
+    #[quickcheck]
+    fn running_combine_exclusive_yields_every_strict_prefix(values: Vec<String>) -> bool {
+        let running = values
+            .iter()
+            .cloned()
+            .running_combine_exclusive()
+            .collect::<Vec<_>>();
+
+        running.len() == values.len()
+            && running
+                .iter()
+                .enumerate()
+                .all(|(i, acc)| *acc == naive(&values[..i]).unwrap_or_default())
+    }
+
+    #[quickcheck]
+    fn suffix_combine_yields_every_suffix(values: Vec<String>) -> bool {
+        let suffixes = values.iter().cloned().suffix_combine().collect::<Vec<_>>();
+
+        suffixes.len() == values.len()
+            && suffixes
+                .iter()
+                .rev()
+                .enumerate()
+                .all(|(i, acc)| Some(acc) == naive(&values[i..]).as_ref())
+    }
+
+    #[quickcheck]
+    fn group_combine_is_the_same_as_filtering_each_key(rows: Vec<(u8, String)>) -> bool {
+        let rows = rows
+            .into_iter()
+            .map(|(key, value)| (key % 4, value))
+            .collect::<Vec<_>>();
+        let groups = rows
+            .iter()
+            .group_combine(|(key, _)| *key, |(_, value)| value.clone());
+        let ordered = rows
+            .iter()
+            .group_combine_ordered(|(key, _)| *key, |(_, value)| value.clone());
+
+        (0..4).all(|key| {
+            let expected = naive(
+                &rows
+                    .iter()
+                    .filter(|(k, _)| *k == key)
+                    .map(|(_, value)| value.clone())
+                    .collect::<Vec<_>>(),
+            );
+
+            groups.get(&key) == expected.as_ref() && ordered.get(&key) == expected.as_ref()
+        })
+    }
+
+    #[quickcheck]
+    fn group_combine_sorted_agrees_with_group_combine(rows: Vec<(u8, u32)>) -> bool {
+        let mut rows = rows;
+        rows.sort_by_key(|(key, _)| *key);
+
+        let sorted = rows
+            .iter()
+            .group_combine_sorted(|(key, _)| *key, |(_, value)| Sum(*value as u64))
+            .collect::<Vec<_>>();
+        let ordered = rows
+            .iter()
+            .group_combine_ordered(|(key, _)| *key, |(_, value)| Sum(*value as u64))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        sorted == ordered
+    }
+
+    #[test]
+    fn group_combine_sorted_only_merges_neighbours() {
+        let groups = ["a", "a", "b", "a"]
+            .into_iter()
+            .group_combine_sorted(|key| *key, |_| Sum(1))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![("a", Sum(2)), ("b", Sum(1)), ("a", Sum(1))], groups);
+    }
+
+    #[test]
+    fn running_max() {
+        let running = [2, 5, 1, 7, 3]
+            .into_iter()
+            .map(Max)
+            .running_combine()
+            .map(|x| x.0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![2, 5, 5, 7, 7], running);
+    }
+
+    #[test]
+    fn exact_size() {
+        let iter = [1, 2, 3].into_iter().map(Sum).running_combine_exclusive();
+
+        assert_eq!(3, iter.len());
+    }
+}
@@ -52,20 +52,104 @@
 //! assert_eq!(expected, total);
 //! ```
 //!
-//! A more elaborate example of the above can be run with `cargo run --example orderline`
+//! A more elaborate example of the above can be run with `cargo run --example orderline --features derive`
+//!
+//! ## Derives
+//! With the `derive` feature, [Semigroup] and [Monoid] can be derived for structs and enums. The derives are
+//! exported from the [prelude] together with the traits.
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use partial_functional::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Semigroup, Monoid)]
+//! struct Stats {
+//!     count: Sum<u32>,
+//!     #[semigroup(via = Max)]
+//!     #[monoid(empty = 0)]
+//!     largest: u32,
+//! }
+//!
+//! let stats = [3, 9, 4]
+//!     .into_iter()
+//!     .map(|x| Stats { count: Sum(1), largest: x })
+//!     .fold(Stats::empty(), Semigroup::combine);
+//!
+//! assert_eq!(Stats { count: Sum(3), largest: 9 }, stats);
+//! # }
+//! ```
+//!
+//! [FunctorMut](functor::FunctorMut) can be derived too, it maps the last type parameter of the type.
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use partial_functional::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, FunctorMut)]
+//! struct Labelled<T> {
+//!     label: &'static str,
+//!     values: Vec<T>,
+//! }
+//!
+//! let labelled = Labelled { label: "ids", values: vec![1, 2] };
+//! assert_eq!(Labelled { label: "ids", values: vec![10, 20] }, labelled.fmap_mut(|x| x * 10));
+//! # }
+//! ```
 
+// Lets the derives refer to `::partial_functional` from inside of this crate as well.
+extern crate self as partial_functional;
+
+pub mod action;
+pub mod collections;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod functor;
+pub mod group;
 pub mod hkt;
+pub mod iter;
+pub mod matrix;
+pub mod metrics;
 pub mod monoid;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod parallel;
+pub mod partial;
 pub mod semigroup;
+pub mod semiring;
+#[cfg(feature = "futures")]
+pub mod stream;
+pub mod sync;
+pub mod tracked;
 
+pub use action::MonoidAction;
+pub use group::Group;
 pub use hkt::*;
-pub use monoid::{All, Any, First, Last, Monoid, Product, Sum, Min, Max};
-pub use semigroup::Semigroup;
+pub use iter::CombineIterator;
+#[cfg(feature = "rayon")]
+pub use par::ParallelCombine;
+pub use partial::{MissingFields, Partial};
+pub use monoid::{All, Any, BitOr, First, Gcd, Last, Monoid, Product, Sum, Min, Max, Xor};
+pub use semigroup::{Idempotent, Semigroup};
+#[cfg(feature = "derive")]
+pub use partfun_derive::{FunctorMut, Monoid, Partial, Patch, Semigroup};
+#[cfg(feature = "futures")]
+pub use stream::MonoidStreamExt;
 
 pub mod prelude {
     pub use crate::{
-        monoid::{All, Any, First, Last, Monoid, Product, Sum, Min, Max},
-        semigroup::Semigroup,
+        action::MonoidAction,
+        functor::{Functor, FunctorMut},
+        group::Group,
+        iter::CombineIterator,
+        monoid::{All, Any, BitOr, First, Gcd, Last, Monoid, Product, Sum, Min, Max, Xor},
+        partial::Partial,
+        semigroup::{Idempotent, Semigroup},
     };
+
+    #[cfg(feature = "derive")]
+    pub use partfun_derive::{FunctorMut, Monoid, Partial, Patch, Semigroup};
+    #[cfg(feature = "rayon")]
+    pub use crate::par::ParallelCombine;
+    #[cfg(feature = "futures")]
+    pub use crate::stream::MonoidStreamExt;
 }
@@ -0,0 +1,345 @@
+use std::ops::{Index, IndexMut};
+
+use crate::semigroup::Semigroup;
+use crate::semiring::Semiring;
+
+/// A square `N`x`N` matrix over a [Semiring]. Matrices form a Semigroup under matrix multiplication and a
+/// [Monoid](crate::Monoid) with the identity matrix as `empty`, so [Monoid::power](crate::Monoid::power)
+/// raises a matrix to a power by repeated squaring.
+///
+/// # Examples
+/// Computing the 10th fibonacci number from the linear recurrence `F(n+1) = F(n) + F(n-1)`.
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::matrix::Matrix;
+///
+/// let step = Matrix::new([[1u64, 1], [1, 0]]);
+/// assert_eq!(55, step.power(10)[(0, 1)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<S, const N: usize>(pub [[S; N]; N]);
+
+impl<S, const N: usize> Matrix<S, N> {
+    pub fn new(rows: [[S; N]; N]) -> Self {
+        Self(rows)
+    }
+
+    pub fn into_inner(self) -> [[S; N]; N] {
+        self.0
+    }
+}
+
+impl<S: Semiring, const N: usize> Matrix<S, N> {
+    /// The matrix with [Semiring::zero] everywhere.
+    pub fn zero() -> Self {
+        Self(std::array::from_fn(|_| std::array::from_fn(|_| S::zero())))
+    }
+
+    /// The matrix with [Semiring::one] on the diagonal and [Semiring::zero] everywhere else.
+    pub fn identity() -> Self {
+        Self(std::array::from_fn(|row| {
+            std::array::from_fn(|col| if row == col { S::one() } else { S::zero() })
+        }))
+    }
+}
+
+impl<S: Semiring, const N: usize> Default for Matrix<S, N> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<S: Semiring, const N: usize> Semigroup for Matrix<S, N> {
+    fn combine(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|row| {
+            std::array::from_fn(|col| {
+                (0..N).fold(S::zero(), |acc, k| {
+                    acc.plus(self.0[row][k].clone().times(rhs.0[k][col].clone()))
+                })
+            })
+        }))
+    }
+}
+
+impl<S, const N: usize> Index<(usize, usize)> for Matrix<S, N> {
+    type Output = S;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.0[row][col]
+    }
+}
+
+impl<S, const N: usize> IndexMut<(usize, usize)> for Matrix<S, N> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        &mut self.0[row][col]
+    }
+}
+
+impl<S, const N: usize> From<[[S; N]; N]> for Matrix<S, N> {
+    fn from(value: [[S; N]; N]) -> Self {
+        Self(value)
+    }
+}
+
+/// A square matrix over a [Semiring] whose size is only known at runtime.
+///
+/// Since [Monoid::empty](crate::Monoid::empty) can't know the size of the matrix, the identity is kept
+/// as a size-less matrix that takes the size of whatever it is combined with. Combining two
+/// sized matrices of different sizes panics.
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::matrix::DynMatrix;
+///
+/// let step = DynMatrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+/// assert_eq!(55, step.power(10).get(0, 1));
+/// assert_eq!(None, DynMatrix::<u64>::empty().size());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynMatrix<S> {
+    repr: Repr<S>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Repr<S> {
+    Identity,
+    Dense { size: usize, data: Vec<S> },
+}
+
+impl<S> DynMatrix<S> {
+    /// Creates a matrix from its rows.
+    ///
+    /// # Panics
+    /// If the rows doesn't make up a square matrix.
+    pub fn from_rows(rows: Vec<Vec<S>>) -> Self {
+        let size = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == size),
+            "a DynMatrix has to be square"
+        );
+
+        Self {
+            repr: Repr::Dense {
+                size,
+                data: rows.into_iter().flatten().collect(),
+            },
+        }
+    }
+
+    /// Returns the number of rows and columns, or None for the size-less identity.
+    pub fn size(&self) -> Option<usize> {
+        match self.repr {
+            Repr::Identity => None,
+            Repr::Dense { size, .. } => Some(size),
+        }
+    }
+}
+
+impl<S: Semiring> DynMatrix<S> {
+    /// The `size`x`size` matrix with [Semiring::zero] everywhere.
+    pub fn zero(size: usize) -> Self {
+        Self {
+            repr: Repr::Dense {
+                size,
+                data: vec![S::zero(); size * size],
+            },
+        }
+    }
+
+    /// The sized identity matrix, unlike [Monoid::empty](crate::Monoid::empty) this one has a size.
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zero(size);
+        if let Repr::Dense { ref mut data, .. } = matrix.repr {
+            for i in 0..size {
+                data[i * size + i] = S::one();
+            }
+        }
+
+        matrix
+    }
+
+    /// Returns the element at `row` and `col`.
+    ///
+    /// # Panics
+    /// If the position is outside of a sized matrix.
+    pub fn get(&self, row: usize, col: usize) -> S {
+        match self.repr {
+            Repr::Identity if row == col => S::one(),
+            Repr::Identity => S::zero(),
+            Repr::Dense { size, ref data } => {
+                assert!(row < size && col < size, "position outside of the matrix");
+                data[row * size + col].clone()
+            }
+        }
+    }
+
+    /// Sets the element at `row` and `col`.
+    ///
+    /// # Panics
+    /// If the matrix is the size-less identity or the position is outside of the matrix.
+    pub fn set(&mut self, row: usize, col: usize, value: S) {
+        match self.repr {
+            Repr::Identity => panic!("can't set an element of the size-less identity"),
+            Repr::Dense { size, ref mut data } => {
+                assert!(row < size && col < size, "position outside of the matrix");
+                data[row * size + col] = value;
+            }
+        }
+    }
+}
+
+impl<S> Default for DynMatrix<S> {
+    fn default() -> Self {
+        Self {
+            repr: Repr::Identity,
+        }
+    }
+}
+
+impl<S: Semiring> Semigroup for DynMatrix<S> {
+    fn combine(self, rhs: Self) -> Self {
+        match (self.repr, rhs.repr) {
+            (Repr::Identity, repr) | (repr, Repr::Identity) => Self { repr },
+            (
+                Repr::Dense { size, data: left },
+                Repr::Dense {
+                    size: rhs_size,
+                    data: right,
+                },
+            ) => {
+                assert_eq!(size, rhs_size, "can't combine matrices of different sizes");
+
+                let data = (0..size * size)
+                    .map(|i| {
+                        let (row, col) = (i / size, i % size);
+                        (0..size).fold(S::zero(), |acc, k| {
+                            acc.plus(
+                                left[row * size + k]
+                                    .clone()
+                                    .times(right[k * size + col].clone()),
+                            )
+                        })
+                    })
+                    .collect();
+
+                Self {
+                    repr: Repr::Dense { size, data },
+                }
+            }
+        }
+    }
+}
+
+impl<S, const N: usize> From<Matrix<S, N>> for DynMatrix<S> {
+    fn from(value: Matrix<S, N>) -> Self {
+        Self::from_rows(value.0.into_iter().map(Vec::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::Monoid;
+    use crate::semiring::MinPlus;
+
+    const INF: MinPlus<u32> = MinPlus(u32::MAX);
+
+    fn fibonacci(n: u64) -> u64 {
+        Matrix::new([[1u64, 1], [1, 0]]).power(n)[(0, 1)]
+    }
+
+    #[test]
+    fn fibonacci_by_repeated_squaring() {
+        let naive = (0..50)
+            .scan((0u64, 1u64), |state, _| {
+                let current = state.0;
+                *state = (state.1, state.0 + state.1);
+                Some(current)
+            })
+            .collect::<Vec<_>>();
+
+        for (n, expected) in naive.into_iter().enumerate() {
+            assert_eq!(expected, fibonacci(n as u64));
+        }
+    }
+
+    #[test]
+    fn count_paths_of_length_k() {
+        // 0 -> 1 -> 2 -> 0 and 0 -> 2
+        let adjacency = Matrix::new([[0u64, 1, 1], [0, 0, 1], [1, 0, 0]]);
+
+        let two_steps = adjacency.clone().power(2);
+        assert_eq!(1, two_steps[(0, 0)]); // 0 -> 2 -> 0
+        assert_eq!(1, two_steps[(0, 2)]); // 0 -> 1 -> 2
+        assert_eq!(0, two_steps[(1, 1)]);
+
+        let three_steps = adjacency.power(3);
+        assert_eq!(1, three_steps[(0, 0)]); // 0 -> 1 -> 2 -> 0
+        assert_eq!(1, three_steps[(1, 1)]); // 1 -> 2 -> 0 -> 1
+        assert_eq!(1, three_steps[(1, 2)]); // 1 -> 2 -> 0 -> 2
+    }
+
+    #[test]
+    fn all_pairs_shortest_paths_with_min_plus() {
+        let weights = Matrix::new([
+            [MinPlus(0), MinPlus(4), MinPlus(1), INF],
+            [INF, MinPlus(0), INF, MinPlus(1)],
+            [INF, MinPlus(2), MinPlus(0), MinPlus(6)],
+            [MinPlus(3), INF, INF, MinPlus(0)],
+        ]);
+
+        let shortest = weights.power(3);
+
+        assert_eq!(
+            Matrix::new([
+                [MinPlus(0), MinPlus(3), MinPlus(1), MinPlus(4)],
+                [MinPlus(4), MinPlus(0), MinPlus(5), MinPlus(1)],
+                [MinPlus(6), MinPlus(2), MinPlus(0), MinPlus(3)],
+                [MinPlus(3), MinPlus(6), MinPlus(4), MinPlus(0)],
+            ]),
+            shortest
+        );
+    }
+
+    #[test]
+    fn unreachable_nodes_stay_unreachable() {
+        let weights = Matrix::new([[MinPlus(0), MinPlus(5)], [INF, MinPlus(0)]]);
+
+        assert_eq!(INF, weights.power(10)[(1, 0)]);
+    }
+
+    #[test]
+    fn identity_is_empty() {
+        let m = Matrix::new([[1, 2], [3, 4]]);
+
+        assert_eq!(m, m.clone().combine(Matrix::empty()));
+        assert_eq!(m, Matrix::empty().combine(m.clone()));
+    }
+
+    #[test]
+    fn dyn_matrix_agrees_with_matrix() {
+        let m = Matrix::new([[0u64, 1, 1], [0, 0, 1], [1, 0, 0]]);
+        let dynamic = DynMatrix::from(m.clone()).power(7);
+        let fixed = m.power(7);
+
+        for row in 0..3 {
+            for col in 0..3 {
+                assert_eq!(fixed[(row, col)], dynamic.get(row, col));
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_matrix_power_of_zero_is_the_sizeless_identity() {
+        let m = DynMatrix::from_rows(vec![vec![2, 0], vec![0, 2]]);
+
+        assert_eq!(DynMatrix::empty(), m.clone().power(0));
+        assert_eq!(m, m.clone().combine(DynMatrix::empty()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn dyn_matrix_combine_of_different_sizes_panics() {
+        DynMatrix::<u32>::identity(2).combine(DynMatrix::identity(3));
+    }
+}
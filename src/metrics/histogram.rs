@@ -0,0 +1,110 @@
+use crate::monoid::Sum;
+use crate::semigroup::Semigroup;
+
+/// Counts how many observations fell in each of a fixed set of buckets, along with their sum and count.
+///
+/// Every bucket is given by its inclusive upper bound, and a last bucket without an upper bound catches everything
+/// else. Two histograms can only be combined when they have the same buckets.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::metrics::Histogram;
+///
+/// let mut morning = Histogram::new([0.1, 0.5]);
+/// morning.observe(0.05);
+/// morning.observe(0.3);
+///
+/// let mut evening = Histogram::new([0.1, 0.5]);
+/// evening.observe(2.0);
+///
+/// let day = morning.combine(evening);
+/// assert_eq!(3, day.count());
+/// assert_eq!(vec![(0.1, 1), (0.5, 2), (f64::INFINITY, 3)], day.cumulative().collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    buckets: Vec<Sum<u64>>,
+    sum: Sum<f64>,
+    count: Sum<u64>,
+}
+
+impl Histogram {
+    /// Creates an empty histogram with the given upper bounds.
+    ///
+    /// # Panics
+    /// If the bounds aren't finite and strictly increasing.
+    pub fn new(bounds: impl Into<Vec<f64>>) -> Self {
+        let bounds = bounds.into();
+        assert!(
+            bounds.iter().all(|bound| bound.is_finite())
+                && bounds.windows(2).all(|pair| pair[0] < pair[1]),
+            "histogram bounds have to be finite and strictly increasing"
+        );
+
+        Self {
+            buckets: vec![Sum(0); bounds.len() + 1],
+            bounds,
+            sum: Sum(0.0),
+            count: Sum(0),
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.partition_point(|&bound| bound < value);
+
+        self.buckets[bucket].0 += 1;
+        self.sum.0 += value;
+        self.count.0 += 1;
+    }
+
+    pub fn bounds(&self) -> &[f64] {
+        &self.bounds
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum.0
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.0
+    }
+
+    /// Iterates over every upper bound, ending with infinity, together with the number of observations less than or
+    /// equal to it.
+    pub fn cumulative(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.bounds
+            .iter()
+            .copied()
+            .chain(Some(f64::INFINITY))
+            .zip(self.buckets.iter())
+            .scan(0, |total, (bound, count)| {
+                *total += count.0;
+                Some((bound, *total))
+            })
+    }
+}
+
+impl Semigroup for Histogram {
+    /// # Panics
+    /// If the histograms have different buckets.
+    fn combine(self, rhs: Self) -> Self {
+        assert_eq!(
+            self.bounds, rhs.bounds,
+            "histograms with different buckets can't be combined"
+        );
+
+        Self {
+            bounds: self.bounds,
+            buckets: self
+                .buckets
+                .into_iter()
+                .zip(rhs.buckets)
+                .map(|(a, b)| a.combine(b))
+                .collect(),
+            sum: self.sum.combine(rhs.sum),
+            count: self.count.combine(rhs.count),
+        }
+    }
+}
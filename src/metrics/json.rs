@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use super::{Metric, Snapshot};
+
+impl Snapshot {
+    /// Renders the snapshot as a JSON array with one object for every metric.
+    ///
+    /// Every object has a `name`, its `labels`, a `type` and either a `value` or, for histograms, the cumulative
+    /// `buckets` with their `sum` and `count`. Values that JSON can't represent, like an unset gauge or infinity,
+    /// are rendered as `null`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+
+        for (i, (name, labels, metric)) in self.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+
+            write!(out, "{{\"name\":{},\"labels\":{{", string(name)).unwrap();
+            for (j, (name, value)) in labels.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write!(out, "{}:{}", string(name), string(value)).unwrap();
+            }
+            out.push('}');
+
+            match metric {
+                Metric::Counter(count) => {
+                    write!(out, ",\"type\":\"counter\",\"value\":{}", count.0)
+                }
+                Metric::Gauge(gauge) => {
+                    write!(out, ",\"type\":\"gauge\",\"value\":{}", number(gauge.0))
+                }
+                Metric::Peak(peak) => write!(out, ",\"type\":\"peak\",\"value\":{}", peak.0),
+                Metric::Histogram(histogram) => {
+                    out.push_str(",\"type\":\"histogram\",\"buckets\":[");
+                    for (j, (bound, count)) in histogram.cumulative().enumerate() {
+                        if j > 0 {
+                            out.push(',');
+                        }
+                        write!(
+                            out,
+                            "{{\"le\":{},\"count\":{}}}",
+                            number(Some(bound)),
+                            count
+                        )
+                        .unwrap();
+                    }
+                    write!(
+                        out,
+                        "],\"sum\":{},\"count\":{}",
+                        number(Some(histogram.sum())),
+                        histogram.count()
+                    )
+                }
+            }
+            .unwrap();
+
+            out.push('}');
+        }
+
+        out.push(']');
+        out
+    }
+}
+
+fn number(value: Option<f64>) -> String {
+    match value {
+        Some(value) if value.is_finite() => format!("{:?}", value),
+        _ => String::from("null"),
+    }
+}
+
+fn string(value: &str) -> String {
+    let mut out = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metrics::Registry;
+
+    use serde_json::{json, Value};
+
+    #[test]
+    fn rendered_json_parses_back() {
+        let registry = Registry::new();
+        registry.increment("jobs_total", &[("queue", "mail \"urgent\"\u{1}")], 4);
+        registry.set("load", &[], 0.75);
+        registry.peak("largest_job", &[], 12);
+        registry.observe("duration", &[], &[1.0], 0.5);
+        registry.observe("duration", &[], &[1.0], 3.0);
+
+        let parsed = serde_json::from_str::<Value>(&registry.snapshot().to_json()).unwrap();
+
+        let expected = json!([
+            {
+                "name": "duration",
+                "labels": {},
+                "type": "histogram",
+                "buckets": [{ "le": 1.0, "count": 1 }, { "le": null, "count": 2 }],
+                "sum": 3.5,
+                "count": 2
+            },
+            { "name": "jobs_total", "labels": { "queue": "mail \"urgent\"\u{1}" }, "type": "counter", "value": 4 },
+            { "name": "largest_job", "labels": {}, "type": "peak", "value": 12 },
+            { "name": "load", "labels": {}, "type": "gauge", "value": 0.75 },
+        ]);
+
+        assert_eq!(expected, parsed);
+    }
+
+    #[test]
+    fn empty_snapshot() {
+        assert_eq!("[]", Snapshot::new().to_json());
+    }
+}
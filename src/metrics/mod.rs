@@ -0,0 +1,550 @@
+//! A registry of named and labelled metrics, where every kind of metric is a [Semigroup].
+//!
+//! Counters are a [Sum], gauges are the [Last] value set, peaks are a [Max] and histograms add up their buckets.
+//! Because of that a [Snapshot] of one registry can be merged into another, which is how metrics from several
+//! processes or threads are aggregated before they're exported to Prometheus or JSON.
+//!
+//! # Examples
+//! ```
+//! use partial_functional::metrics::Registry;
+//!
+//! let registry = Registry::new();
+//! registry.increment("http_requests_total", &[("method", "GET")], 1);
+//! registry.increment("http_requests_total", &[("method", "GET")], 2);
+//! registry.set("temperature_celsius", &[], 21.5);
+//!
+//! let text = registry.snapshot().to_prometheus();
+//! assert!(text.contains("http_requests_total{method=\"GET\"} 3\n"));
+//! assert!(text.contains("temperature_celsius 21.5\n"));
+//! ```
+
+mod histogram;
+mod json;
+mod prometheus;
+
+pub use histogram::Histogram;
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::sync::{Mutex, PoisonError};
+
+use crate::monoid::{Last, Max, Sum};
+use crate::semigroup::Semigroup;
+
+/// The labels of a metric, kept sorted by name so they are always rendered the same way.
+pub type Labels = BTreeMap<String, String>;
+
+/// A single metric, combining two metrics of a different kind panics.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Metric {
+    Counter(Sum<u64>),
+    Gauge(Last<f64>),
+    Peak(Max<u64>),
+    Histogram(Histogram),
+}
+
+impl Metric {
+    /// The name of this kind of metric, as used by the Prometheus `# TYPE` line.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Metric::Counter(_) => "counter",
+            Metric::Gauge(_) | Metric::Peak(_) => "gauge",
+            Metric::Histogram(_) => "histogram",
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            Metric::Counter(_) => "counter",
+            Metric::Gauge(_) => "gauge",
+            Metric::Peak(_) => "peak",
+            Metric::Histogram(_) => "histogram",
+        }
+    }
+
+    /// Whether the metrics are of the same kind, and histograms have the same buckets.
+    fn combines_with(&self, rhs: &Self) -> bool {
+        match (self, rhs) {
+            (Metric::Histogram(a), Metric::Histogram(b)) => a.bounds() == b.bounds(),
+            (a, b) => mem::discriminant(a) == mem::discriminant(b),
+        }
+    }
+}
+
+impl Semigroup for Metric {
+    fn combine(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Metric::Counter(a), Metric::Counter(b)) => Metric::Counter(a.combine(b)),
+            (Metric::Gauge(a), Metric::Gauge(b)) => Metric::Gauge(a.combine(b)),
+            (Metric::Peak(a), Metric::Peak(b)) => Metric::Peak(a.combine(b)),
+            (Metric::Histogram(a), Metric::Histogram(b)) => Metric::Histogram(a.combine(b)),
+            (a, b) => panic!(
+                "a {} can't be combined with a {}",
+                a.describe(),
+                b.describe()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    name: String,
+    labels: Labels,
+}
+
+/// A metric that couldn't be recorded, because a metric with the same name, under any labels, already exists with a
+/// different kind or with different histogram buckets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub name: String,
+    pub labels: Labels,
+    /// The kind of the metric that was already recorded.
+    pub existing: &'static str,
+    /// The kind of the metric that was rejected.
+    pub incoming: &'static str,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "metric `{}`", self.name)?;
+        if !self.labels.is_empty() {
+            let labels = self
+                .labels
+                .iter()
+                .map(|(name, value)| format!("{}={:?}", name, value))
+                .collect::<Vec<_>>();
+            write!(f, " {{{}}}", labels.join(", "))?;
+        }
+
+        if self.existing == self.incoming {
+            write!(f, " is a {} with different buckets", self.existing)
+        } else {
+            write!(
+                f,
+                " is a {} and can't be combined with a {}",
+                self.existing, self.incoming
+            )
+        }
+    }
+}
+
+impl Error for Conflict {}
+
+/// The metrics of a [Registry] at one point in time.
+///
+/// Merging two snapshots combines the metrics they have in common and keeps the rest, with an empty snapshot as the
+/// identity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    metrics: BTreeMap<Key, Metric>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.metrics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<&Metric> {
+        self.metrics.get(&key(name, labels))
+    }
+
+    /// Iterates over the metrics sorted by name and then by labels.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Labels, &Metric)> {
+        self.metrics
+            .iter()
+            .map(|(key, metric)| (key.name.as_str(), &key.labels, metric))
+    }
+
+    /// Combines `metric` into the metric with the same name and labels, or gives back a [Conflict] and leaves the
+    /// snapshot as it was if a metric with that name has a different kind, whatever its labels.
+    pub fn record(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        metric: Metric,
+    ) -> Result<(), Conflict> {
+        self.insert(key(name, labels), metric)
+    }
+
+    /// Combines all the metrics of `other`, like a snapshot taken in another process, into this snapshot.
+    ///
+    /// A metric that exists in both with a different kind is skipped, and every one of them is given back as a
+    /// [Conflict] after the rest have been merged.
+    pub fn merge(&mut self, other: Snapshot) -> Result<(), Vec<Conflict>> {
+        let conflicts = other
+            .metrics
+            .into_iter()
+            .filter_map(|(key, metric)| self.insert(key, metric).err())
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    fn insert(&mut self, key: Key, metric: Metric) -> Result<(), Conflict> {
+        // Every label set of a name has to be of the same kind, so any of them will do to check against.
+        let named = Key {
+            name: key.name.clone(),
+            labels: Labels::new(),
+        };
+        let sibling = self
+            .metrics
+            .range(named..)
+            .next()
+            .filter(|(other, _)| other.name == key.name);
+        let existing = self.metrics.get(&key).or(sibling.map(|(_, metric)| metric));
+
+        if let Some(existing) = existing.filter(|existing| !existing.combines_with(&metric)) {
+            return Err(Conflict {
+                existing: existing.describe(),
+                incoming: metric.describe(),
+                name: key.name,
+                labels: key.labels,
+            });
+        }
+
+        match self.metrics.get_mut(&key) {
+            Some(existing) => {
+                let combined = mem::replace(existing, Metric::Counter(Sum(0))).combine(metric);
+                *existing = combined;
+            }
+            None => {
+                self.metrics.insert(key, metric);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Semigroup for Snapshot {
+    /// # Panics
+    /// If a metric exists in both with a different kind, use [Snapshot::merge] to handle that instead.
+    fn combine(mut self, rhs: Self) -> Self {
+        if let Err(conflicts) = self.merge(rhs) {
+            panic!("{}", conflicts[0]);
+        }
+
+        self
+    }
+}
+
+/// A registry that metrics can be recorded to from any number of threads.
+#[derive(Debug, Default)]
+pub struct Registry {
+    snapshot: Mutex<Snapshot>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `by` to a counter.
+    ///
+    /// # Panics
+    /// If the metric was recorded with a different kind before, like the other shorthands below.
+    pub fn increment(&self, name: &str, labels: &[(&str, &str)], by: u64) {
+        self.expect(name, labels, Metric::Counter(Sum(by)));
+    }
+
+    /// Sets a gauge to `value`.
+    pub fn set(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.expect(name, labels, Metric::Gauge(Last::from(value)));
+    }
+
+    /// Raises a peak to `value` if it is higher than what has been recorded so far.
+    pub fn peak(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+        self.expect(name, labels, Metric::Peak(Max(value)));
+    }
+
+    /// Records `value` in a histogram with the given bucket bounds.
+    ///
+    /// # Panics
+    /// If the histogram was recorded with other bounds before.
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], bounds: &[f64], value: f64) {
+        let mut histogram = Histogram::new(bounds);
+        histogram.observe(value);
+
+        self.expect(name, labels, Metric::Histogram(histogram));
+    }
+
+    /// Combines `metric` into the metric with the same name and labels, see [Snapshot::record].
+    pub fn record(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+        metric: Metric,
+    ) -> Result<(), Conflict> {
+        self.lock().record(name, labels, metric)
+    }
+
+    /// Combines all the metrics of `snapshot`, like one taken from another process, into this registry. Metrics that
+    /// have a different kind in the registry are skipped and given back, see [Snapshot::merge].
+    pub fn merge(&self, snapshot: Snapshot) -> Result<(), Vec<Conflict>> {
+        self.lock().merge(snapshot)
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.lock().clone()
+    }
+
+    /// Takes all the metrics out of the registry, leaving it empty.
+    pub fn take(&self) -> Snapshot {
+        mem::take(&mut *self.lock())
+    }
+
+    fn expect(&self, name: &str, labels: &[(&str, &str)], metric: Metric) {
+        if let Err(conflict) = self.record(name, labels, metric) {
+            panic!("{}", conflict);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Snapshot> {
+        self.snapshot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn key(name: &str, labels: &[(&str, &str)]) -> Key {
+    Key {
+        name: name.to_owned(),
+        labels: labels
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::Monoid;
+
+    use std::thread;
+
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn merging_snapshots_is_the_same_as_recording_to_one(
+        counts: Vec<(u8, u16)>,
+        split: usize,
+    ) -> bool {
+        let record = |snapshot: &mut Snapshot, &(label, count): &(u8, u16)| {
+            let label = (label % 3).to_string();
+            snapshot
+                .record(
+                    "jobs",
+                    &[("queue", &label)],
+                    Metric::Counter(Sum(count as u64)),
+                )
+                .unwrap();
+            snapshot
+                .record("largest_job", &[], Metric::Peak(Max(count as u64)))
+                .unwrap();
+            snapshot
+                .record(
+                    "last_job",
+                    &[("queue", &label)],
+                    Metric::Gauge(Last::from(count as f64)),
+                )
+                .unwrap();
+        };
+
+        let split = split % (counts.len() + 1);
+        let (left, right) = counts.split_at(split);
+
+        let mut one = Snapshot::new();
+        counts.iter().for_each(|count| record(&mut one, count));
+
+        let mut first = Snapshot::new();
+        let mut second = Snapshot::new();
+        left.iter().for_each(|count| record(&mut first, count));
+        right.iter().for_each(|count| record(&mut second, count));
+
+        one == first.combine(second)
+    }
+
+    #[test]
+    fn registry_is_shared_between_threads() {
+        let registry = Registry::new();
+
+        thread::scope(|scope| {
+            for t in 0..4 {
+                let registry = &registry;
+                scope.spawn(move || {
+                    for i in 0..100 {
+                        registry.increment("events_total", &[], 1);
+                        registry.peak("largest_event", &[], t * 100 + i);
+                        registry.observe("event_size", &[], &[10.0, 100.0], i as f64);
+                    }
+                });
+            }
+        });
+
+        let snapshot = registry.snapshot();
+        assert_eq!(
+            Some(&Metric::Counter(Sum(400))),
+            snapshot.get("events_total", &[])
+        );
+        assert_eq!(
+            Some(&Metric::Peak(Max(399))),
+            snapshot.get("largest_event", &[])
+        );
+
+        let Some(Metric::Histogram(histogram)) = snapshot.get("event_size", &[]) else {
+            panic!("event_size should be a histogram");
+        };
+        assert_eq!(
+            vec![(10.0, 44), (100.0, 400), (f64::INFINITY, 400)],
+            histogram.cumulative().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merge_from_another_process() {
+        let registry = Registry::new();
+        registry.increment("requests_total", &[("code", "200")], 5);
+
+        let mut other = Snapshot::empty();
+        other
+            .record(
+                "requests_total",
+                &[("code", "200")],
+                Metric::Counter(Sum(2)),
+            )
+            .unwrap();
+        other
+            .record(
+                "requests_total",
+                &[("code", "500")],
+                Metric::Counter(Sum(1)),
+            )
+            .unwrap();
+        registry.merge(other).unwrap();
+
+        let snapshot = registry.take();
+        assert_eq!(2, snapshot.len());
+        assert_eq!(
+            Some(&Metric::Counter(Sum(7))),
+            snapshot.get("requests_total", &[("code", "200")])
+        );
+        assert!(registry.snapshot().is_empty());
+    }
+
+    #[test]
+    fn merging_a_different_kind_skips_and_reports_it() {
+        let registry = Registry::new();
+        registry.increment("temperature", &[("room", "hall")], 1);
+        registry.observe("latency", &[], &[1.0], 0.5);
+
+        let mut other = Snapshot::new();
+        other
+            .record(
+                "temperature",
+                &[("room", "hall")],
+                Metric::Gauge(Last::from(21.5)),
+            )
+            .unwrap();
+        other
+            .record("latency", &[], Metric::Histogram(Histogram::new([2.0])))
+            .unwrap();
+        other
+            .record("requests_total", &[], Metric::Counter(Sum(3)))
+            .unwrap();
+
+        let conflicts = registry.merge(other).unwrap_err();
+        assert_eq!(
+            vec![
+                "metric `latency` is a histogram with different buckets",
+                "metric `temperature` {room=\"hall\"} is a counter and can't be combined with a gauge",
+            ],
+            conflicts.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+
+        let snapshot = registry.snapshot();
+        assert_eq!(3, snapshot.len());
+        assert_eq!(
+            Some(&Metric::Counter(Sum(1))),
+            snapshot.get("temperature", &[("room", "hall")])
+        );
+        assert_eq!(
+            Some(&Metric::Counter(Sum(3))),
+            snapshot.get("requests_total", &[])
+        );
+    }
+
+    #[test]
+    fn recording_a_different_kind_is_a_conflict() {
+        let registry = Registry::new();
+        registry.peak("queue_depth", &[], 4);
+
+        let conflict = registry
+            .record("queue_depth", &[], Metric::Counter(Sum(1)))
+            .unwrap_err();
+        assert_eq!(("peak", "counter"), (conflict.existing, conflict.incoming));
+        assert_eq!(
+            Some(&Metric::Peak(Max(4))),
+            registry.snapshot().get("queue_depth", &[])
+        );
+    }
+
+    #[test]
+    fn a_name_has_one_kind_whatever_the_labels() {
+        let registry = Registry::new();
+        registry.increment("x", &[("a", "1")], 1);
+
+        let conflict = registry
+            .record("x", &[("a", "2")], Metric::Gauge(Last::from(2.0)))
+            .unwrap_err();
+        assert_eq!(
+            "metric `x` {a=\"2\"} is a counter and can't be combined with a gauge",
+            conflict.to_string()
+        );
+        assert_eq!(1, registry.snapshot().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be combined")]
+    fn different_kinds_panic() {
+        let registry = Registry::new();
+        registry.increment("temperature", &[], 1);
+        registry.set("temperature", &[], 21.5);
+    }
+
+    #[test]
+    fn histograms_add_up_their_buckets() {
+        let mut a = Histogram::new([1.0, 2.0]);
+        let mut b = Histogram::new([1.0, 2.0]);
+        [0.5, 1.0, 3.0].into_iter().for_each(|x| a.observe(x));
+        [1.5].into_iter().for_each(|x| b.observe(x));
+
+        let total = a.combine(b);
+        assert_eq!(4, total.count());
+        assert_eq!(6.0, total.sum());
+        assert_eq!(
+            vec![(1.0, 2), (2.0, 3), (f64::INFINITY, 4)],
+            total.cumulative().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "different buckets")]
+    fn histograms_with_different_buckets_panic() {
+        Histogram::new([1.0]).combine(Histogram::new([2.0]));
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt::Write;
+
+use super::{Labels, Metric, Snapshot};
+
+impl Snapshot {
+    /// Renders the snapshot in the Prometheus text exposition format.
+    ///
+    /// Gauges that were never set are left out, and a histogram is rendered as its cumulative `_bucket`s followed by
+    /// its `_sum` and `_count`.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut current = None;
+
+        for (name, labels, metric) in self.iter() {
+            if current != Some(name) {
+                writeln!(out, "# TYPE {} {}", name, metric.kind()).unwrap();
+                current = Some(name);
+            }
+
+            match metric {
+                Metric::Counter(count) => sample(&mut out, name, labels, None, count.0 as f64),
+                Metric::Gauge(gauge) => {
+                    if let Some(value) = gauge.0 {
+                        sample(&mut out, name, labels, None, value);
+                    }
+                }
+                Metric::Peak(peak) => sample(&mut out, name, labels, None, peak.0 as f64),
+                Metric::Histogram(histogram) => {
+                    let bucket = format!("{}_bucket", name);
+                    for (bound, count) in histogram.cumulative() {
+                        sample(&mut out, &bucket, labels, Some(bound), count as f64);
+                    }
+
+                    sample(
+                        &mut out,
+                        &format!("{}_sum", name),
+                        labels,
+                        None,
+                        histogram.sum(),
+                    );
+                    sample(
+                        &mut out,
+                        &format!("{}_count", name),
+                        labels,
+                        None,
+                        histogram.count() as f64,
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn sample(out: &mut String, name: &str, labels: &Labels, le: Option<f64>, value: f64) {
+    out.push_str(name);
+
+    let le = le.map(|bound| ("le", float(bound)));
+    let mut labels = labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), escape(value)))
+        .chain(le)
+        .peekable();
+
+    if labels.peek().is_some() {
+        out.push('{');
+        for (i, (name, value)) in labels.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, "{}=\"{}\"", name, value).unwrap();
+        }
+        out.push('}');
+    }
+
+    writeln!(out, " {}", float(value)).unwrap();
+}
+
+fn float(value: f64) -> String {
+    match value {
+        f64::INFINITY => String::from("+Inf"),
+        f64::NEG_INFINITY => String::from("-Inf"),
+        value if value.is_nan() => String::from("NaN"),
+        value => value.to_string(),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metrics::Registry;
+
+    use std::collections::BTreeMap;
+
+    type Sample = (String, BTreeMap<String, String>);
+
+    /// Parses the samples of the text format back into their name, labels and value.
+    fn parse(text: &str) -> BTreeMap<Sample, f64> {
+        text.lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| {
+                let (series, value) = line.rsplit_once(' ').unwrap();
+                let value = match value {
+                    "+Inf" => f64::INFINITY,
+                    value => value.parse().unwrap(),
+                };
+
+                let Some((name, rest)) = series.split_once('{') else {
+                    return ((series.to_owned(), BTreeMap::new()), value);
+                };
+
+                let mut labels = BTreeMap::new();
+                let mut chars = rest.strip_suffix('}').unwrap().chars().peekable();
+                while chars.peek().is_some() {
+                    let label = chars.by_ref().take_while(|&c| c != '=').collect::<String>();
+                    assert_eq!(Some('"'), chars.next());
+
+                    let mut value = String::new();
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '"' => break,
+                            '\\' => match chars.next() {
+                                Some('n') => value.push('\n'),
+                                Some(c) => value.push(c),
+                                None => panic!("dangling escape"),
+                            },
+                            c => value.push(c),
+                        }
+                    }
+                    chars.next_if_eq(&',');
+                    labels.insert(label, value);
+                }
+
+                ((name.to_owned(), labels), value)
+            })
+            .collect()
+    }
+
+    fn sample(name: &str, labels: &[(&str, &str)]) -> Sample {
+        (
+            name.to_owned(),
+            labels
+                .iter()
+                .map(|&(k, v)| (k.to_owned(), v.to_owned()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn rendered_samples_parse_back() {
+        let registry = Registry::new();
+        registry.increment(
+            "requests_total",
+            &[("path", "/a \"quoted\" \\ path\n"), ("code", "200")],
+            3,
+        );
+        registry.increment("requests_total", &[("path", "/b"), ("code", "500")], 1);
+        registry.set("temperature", &[], -3.25);
+        registry.peak("queue_peak", &[("queue", "mail")], 17);
+        for value in [0.05, 0.2, 0.2, 4.0] {
+            registry.observe("latency_seconds", &[("route", "home")], &[0.1, 1.0], value);
+        }
+
+        let text = registry.snapshot().to_prometheus();
+        let samples = parse(&text);
+
+        let expected = [
+            (
+                sample(
+                    "requests_total",
+                    &[("path", "/a \"quoted\" \\ path\n"), ("code", "200")],
+                ),
+                3.0,
+            ),
+            (
+                sample("requests_total", &[("path", "/b"), ("code", "500")]),
+                1.0,
+            ),
+            (sample("temperature", &[]), -3.25),
+            (sample("queue_peak", &[("queue", "mail")]), 17.0),
+            (
+                sample(
+                    "latency_seconds_bucket",
+                    &[("route", "home"), ("le", "0.1")],
+                ),
+                1.0,
+            ),
+            (
+                sample("latency_seconds_bucket", &[("route", "home"), ("le", "1")]),
+                3.0,
+            ),
+            (
+                sample(
+                    "latency_seconds_bucket",
+                    &[("route", "home"), ("le", "+Inf")],
+                ),
+                4.0,
+            ),
+            (sample("latency_seconds_sum", &[("route", "home")]), 4.45),
+            (sample("latency_seconds_count", &[("route", "home")]), 4.0),
+        ]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(expected, samples);
+
+        // Prometheus has no peak type, so a peak is exposed as the gauge it is.
+        let types = text
+            .lines()
+            .filter(|line| line.starts_with("# TYPE"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "# TYPE latency_seconds histogram",
+                "# TYPE queue_peak gauge",
+                "# TYPE requests_total counter",
+                "# TYPE temperature gauge",
+            ],
+            types
+        );
+    }
+
+    #[test]
+    fn one_type_line_per_metric() {
+        let registry = Registry::new();
+        registry.increment("a_total", &[("x", "1")], 1);
+        registry.increment("a_total", &[("x", "2")], 1);
+        registry.observe("b", &[], &[1.0], 0.5);
+
+        let text = registry.snapshot().to_prometheus();
+        let types = text
+            .lines()
+            .filter(|line| line.starts_with("# TYPE"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["# TYPE a_total counter", "# TYPE b histogram"], types);
+    }
+
+    #[test]
+    fn unset_gauges_are_left_out() {
+        let mut snapshot = Snapshot::new();
+        snapshot
+            .record("idle", &[], Metric::Gauge(Default::default()))
+            .unwrap();
+
+        assert_eq!("# TYPE idle gauge\n", snapshot.to_prometheus());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::semigroup::Semigroup;
+use crate::semigroup::{Idempotent, Semigroup};
 
 /// Boolean semigroup over the operator &&.
 /// ```
@@ -16,6 +16,8 @@ impl Semigroup for All {
     }
 }
 
+impl Idempotent for All {}
+
 impl Default for All {
     fn default() -> Self {
         Self(true)
@@ -73,6 +75,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn ordering_with_primitive() {
         let any = All::from(true);
 
@@ -1,4 +1,4 @@
-use crate::semigroup::Semigroup;
+use crate::semigroup::{Idempotent, Semigroup};
 
 /// Boolean semigroup over the operator ||.
 /// ```
@@ -7,7 +7,7 @@ use crate::semigroup::Semigroup;
 /// assert_eq!(Any(true), Any(true).combine(Any(true)));
 /// assert_eq!(Any(false), Any(false).combine(Any(false)));
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Any(pub bool);
 
 impl Semigroup for Any {
@@ -16,6 +16,8 @@ impl Semigroup for Any {
     }
 }
 
+impl Idempotent for Any {}
+
 impl PartialEq<bool> for Any {
     fn eq(&self, other: &bool) -> bool {
         self.0 == *other
@@ -28,12 +30,6 @@ impl PartialOrd<bool> for Any {
     }
 }
 
-impl Default for Any {
-    fn default() -> Self {
-        Self(false)
-    }
-}
-
 impl From<bool> for Any {
     fn from(value: bool) -> Self {
         Self(value)
@@ -73,6 +69,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn ordering_with_primitive() {
         let any = Any::from(true);
 
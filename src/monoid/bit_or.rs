@@ -0,0 +1,103 @@
+use crate::semigroup::{Idempotent, Semigroup};
+
+/// Semigroup over the bitwise or of T if T implements [std::ops::BitOr].
+/// ```
+/// # use partial_functional::{BitOr, Semigroup};
+/// assert_eq!(BitOr(0b1110), BitOr(0b1100).combine(BitOr(0b1010)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitOr<T>(pub T);
+
+impl<T: Default + std::ops::BitOr<Output = T>> Default for BitOr<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for BitOr<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T: PartialOrd> PartialOrd<T> for BitOr<T> {
+    fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl<T> From<T> for BitOr<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: std::ops::BitOr<Output = T>> Semigroup for BitOr<T> {
+    fn combine(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl<T: std::ops::BitOr<Output = T>> Idempotent for BitOr<T> {}
+
+macro_rules! impl_from {
+    (
+        $($t:ty),* $(,)?
+    ) => {
+        $(
+            impl From<BitOr<$t>> for $t {
+                fn from(value: BitOr<$t>) -> Self {
+                    value.0
+                }
+            }
+        )*
+    }
+}
+
+impl_from!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
+#[cfg(test)]
+mod tests {
+    use crate::monoid::Monoid;
+
+    use super::*;
+
+    use quickcheck::Arbitrary;
+    use quickcheck_macros::quickcheck;
+
+    impl Arbitrary for BitOr<u32> {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            BitOr(u32::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(u32::shrink(&self.0).map(|x| x.into()))
+        }
+    }
+
+    #[quickcheck]
+    fn identity_property() {
+        assert_eq!(BitOr::<u32>(0), BitOr::empty())
+    }
+
+    #[quickcheck]
+    fn bit_or_of_vec_is_same_as_bit_or_combine(vec: Vec<BitOr<u32>>) -> bool {
+        let left: BitOr<u32> = vec.iter().fold(0, |a, x| a | x.0).into();
+        let right = vec
+            .iter()
+            .copied()
+            .fold(BitOr::default(), |a, x| a.combine(x));
+
+        left == right
+    }
+
+    #[quickcheck]
+    fn associativity_property(x: BitOr<u32>, y: BitOr<u32>, z: BitOr<u32>) -> bool {
+        x.combine(y.combine(z)) == x.combine(y).combine(z)
+    }
+
+    #[quickcheck]
+    fn idempotent_property(x: BitOr<u32>) -> bool {
+        x.combine(x) == x
+    }
+}
@@ -1,4 +1,4 @@
-use crate::semigroup::Semigroup;
+use crate::semigroup::{Idempotent, Semigroup};
 
 /// A semigroup over Option by returning the first value available.
 /// ```
@@ -43,6 +43,8 @@ impl<T> Semigroup for First<T> {
     }
 }
 
+impl<T> Idempotent for First<T> {}
+
 #[cfg(test)]
 mod tests {
     use crate::monoid::Monoid;
@@ -0,0 +1,129 @@
+use crate::semigroup::{Idempotent, Semigroup};
+
+/// Semigroup over the greatest common divisor of unsigned integers, with zero as the identity since every number
+/// divides it.
+/// ```
+/// # use partial_functional::{Gcd, Semigroup};
+/// assert_eq!(Gcd(6u32), Gcd(12).combine(Gcd(18)));
+/// assert_eq!(Gcd(5u8), Gcd(0).combine(Gcd(5)));
+/// ```
+///
+/// Signed integers aren't supported, since `Gcd(-4)` combined with itself or with zero would give back `Gcd(4)`,
+/// breaking the identity and idempotence laws. Use the [unsigned_abs](i32::unsigned_abs) of the values instead.
+/// ```compile_fail
+/// # use partial_functional::{Gcd, Semigroup};
+/// Gcd(-8i32).combine(Gcd(12));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gcd<T>(pub T);
+
+impl<T: Default> Default for Gcd<T>
+where
+    Gcd<T>: Semigroup,
+{
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for Gcd<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T: PartialOrd> PartialOrd<T> for Gcd<T> {
+    fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl<T> From<T> for Gcd<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+fn euclid<T: Copy + PartialEq + Default + std::ops::Rem<Output = T>>(mut a: T, mut b: T) -> T {
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+macro_rules! impl_gcd {
+    ( $($t:ty),* $(,)? ) => {
+        $(
+            impl Semigroup for Gcd<$t> {
+                fn combine(self, rhs: Self) -> Self {
+                    Self(euclid(self.0, rhs.0))
+                }
+            }
+
+            impl Idempotent for Gcd<$t> {}
+
+            impl From<Gcd<$t>> for $t {
+                fn from(value: Gcd<$t>) -> Self {
+                    value.0
+                }
+            }
+        )*
+    };
+}
+
+impl_gcd!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use crate::monoid::Monoid;
+
+    use super::*;
+
+    use quickcheck::Arbitrary;
+    use quickcheck_macros::quickcheck;
+
+    impl Arbitrary for Gcd<u32> {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            Gcd(u32::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(u32::shrink(&self.0).map(|x| x.into()))
+        }
+    }
+
+    #[quickcheck]
+    fn identity_property(x: Gcd<u32>) -> bool {
+        Gcd::empty().combine(x) == x && x.combine(Gcd::empty()) == x
+    }
+
+    #[quickcheck]
+    fn gcd_divides_both(x: Gcd<u32>, y: Gcd<u32>) -> bool {
+        let gcd = x.combine(y).0;
+
+        gcd == 0 || (x.0.is_multiple_of(gcd) && y.0.is_multiple_of(gcd))
+    }
+
+    #[quickcheck]
+    fn associativity_property(x: Gcd<u32>, y: Gcd<u32>, z: Gcd<u32>) -> bool {
+        x.combine(y.combine(z)) == x.combine(y).combine(z)
+    }
+
+    #[quickcheck]
+    fn laws_hold_for_every_width(x: u8, y: u8) -> bool {
+        let (x, y) = (Gcd(x as u64), Gcd(y as u64));
+
+        x.combine(x) == x
+            && Gcd::empty().combine(x) == x
+            && x.combine(y).0 == Gcd(x.0 as u8).combine(Gcd(y.0 as u8)).0 as u64
+    }
+
+    #[test]
+    fn unsigned_abs_gives_the_gcd_of_signed_values() {
+        let gcd = Gcd((-8i32).unsigned_abs()).combine(Gcd(12i32.unsigned_abs()));
+
+        assert_eq!(Gcd(4), gcd);
+        assert_eq!(Gcd(i64::MIN.unsigned_abs()), Gcd(i64::MIN.unsigned_abs()).combine(Gcd::empty()));
+    }
+}
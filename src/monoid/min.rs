@@ -1,4 +1,4 @@
-use crate::semigroup::Semigroup;
+use crate::semigroup::{Idempotent, Semigroup};
 use num_traits::Bounded;
 
 /// Semigroup over the Minimum of T if T implements [std::cmp::Ord].
@@ -39,6 +39,8 @@ impl<T: Ord> Semigroup for Min<T> {
     }
 }
 
+impl<T: Ord> Idempotent for Min<T> {}
+
 macro_rules! impl_from {
     (
         $($t:ty),* $(,)?
@@ -65,6 +67,7 @@ mod tests {
     use quickcheck_macros::quickcheck;
 
     impl Arbitrary for Min<u32> {
+        #[allow(clippy::useless_conversion)]
         fn arbitrary(g: &mut quickcheck::Gen) -> Self {
             Min(u32::arbitrary(g).into())
         }
@@ -6,6 +6,9 @@ mod any;
 mod all;
 mod min;
 mod max;
+mod bit_or;
+mod gcd;
+mod xor;
 
 pub use self::{
     last::Last,
@@ -16,12 +19,43 @@ pub use self::{
     all::All,
     min::Min,
     max::Max,
+    bit_or::BitOr,
+    gcd::Gcd,
+    xor::Xor,
 };
 
 use crate::semigroup::Semigroup;
 
 pub trait Monoid: Semigroup {
     fn empty() -> Self;
+
+    /// Combines a value with itself `exp` times by repeated squaring, using O(log exp) combines.
+    /// A power of zero gives back [Monoid::empty].
+    /// ```
+    /// # use partial_functional::prelude::*;
+    /// assert_eq!(Sum(12), Sum(3).power(4));
+    /// assert_eq!(Product(1), Product(3).power(0));
+    /// ```
+    fn power(self, mut exp: u64) -> Self
+    where
+        Self: Clone,
+    {
+        let mut base = self;
+        let mut acc = Self::empty();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.combine(base.clone());
+            }
+
+            exp >>= 1;
+            if exp > 0 {
+                base = base.clone().combine(base);
+            }
+        }
+
+        acc
+    }
 }
 
 impl<T: Semigroup + Default> Monoid for T {
@@ -64,6 +98,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::let_and_return)]
     fn combine_macro() {
         let x = crate::combine! {
             Last::from(53), None, 42, {let b = None; b},
@@ -72,6 +107,21 @@ mod tests {
         assert_eq!(x.0, Some(42));
     }
 
+    #[quickcheck_macros::quickcheck]
+    fn power_is_the_same_as_repeated_combine(value: u8, exp: u8) -> bool {
+        let value = Sum(value as u64);
+        let repeated = (0..exp).fold(Sum::empty(), |acc, _| acc.combine(value));
+
+        value.power(exp as u64) == repeated
+    }
+
+    #[test]
+    fn power_keeps_the_order_of_combines() {
+        let s = String::from("ab").power(3);
+
+        assert_eq!(s, "ababab");
+    }
+
     #[test]
     fn last_to_option_conversion() {
         let last = Last::from(42);
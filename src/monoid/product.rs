@@ -102,6 +102,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn product_of_vec_is_same_as_product_combine() {
         let gen = Gen::new(15);
         let mut qtest = quickcheck::QuickCheck::new().gen(gen);
@@ -0,0 +1,96 @@
+use crate::semigroup::Semigroup;
+
+/// Semigroup over the bitwise exclusive or of T if T implements [std::ops::BitXor].
+/// ```
+/// # use partial_functional::{Xor, Semigroup};
+/// assert_eq!(Xor(0b0110), Xor(0b1100).combine(Xor(0b1010)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Xor<T>(pub T);
+
+impl<T: Default + std::ops::BitXor<Output = T>> Default for Xor<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for Xor<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T: PartialOrd> PartialOrd<T> for Xor<T> {
+    fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl<T> From<T> for Xor<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: std::ops::BitXor<Output = T>> Semigroup for Xor<T> {
+    fn combine(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+macro_rules! impl_from {
+    (
+        $($t:ty),* $(,)?
+    ) => {
+        $(
+            impl From<Xor<$t>> for $t {
+                fn from(value: Xor<$t>) -> Self {
+                    value.0
+                }
+            }
+        )*
+    }
+}
+
+impl_from!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
+#[cfg(test)]
+mod tests {
+    use crate::monoid::Monoid;
+
+    use super::*;
+
+    use quickcheck::Arbitrary;
+    use quickcheck_macros::quickcheck;
+
+    impl Arbitrary for Xor<u32> {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            Xor(u32::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(u32::shrink(&self.0).map(|x| x.into()))
+        }
+    }
+
+    #[quickcheck]
+    fn identity_property() {
+        assert_eq!(Xor::<u32>(0), Xor::empty())
+    }
+
+    #[quickcheck]
+    fn xor_of_vec_is_same_as_xor_combine(vec: Vec<Xor<u32>>) -> bool {
+        let left: Xor<u32> = vec.iter().fold(0, |a, x| a ^ x.0).into();
+        let right = vec
+            .iter()
+            .copied()
+            .fold(Xor::default(), |a, x| a.combine(x));
+
+        left == right
+    }
+
+    #[quickcheck]
+    fn associativity_property(x: Xor<u32>, y: Xor<u32>, z: Xor<u32>) -> bool {
+        x.combine(y.combine(z)) == x.combine(y).combine(z)
+    }
+}
@@ -0,0 +1,175 @@
+//! Parallel folding of [Monoid]s with [rayon], enabled by the `rayon` feature.
+//!
+//! Monoids are associative, so rayon is free to split the work into chunks, combine every chunk on its own thread and
+//! then combine the results. Indexed parallel iterators, like the ones from a [Vec] or a range, combine the chunks in
+//! the original order, which means even monoids that aren't commutative like [First] or [Vec] give the same result as
+//! a sequential fold.
+//!
+//! # Examples
+//! ```
+//! use partial_functional::prelude::*;
+//! use rayon::prelude::*;
+//!
+//! let total = (1..=100u64).into_par_iter().par_fold_map(Sum);
+//! assert_eq!(Sum(5050), total);
+//!
+//! let largest: Max<u64> = (1..=100u64).into_par_iter().map(Max).collect();
+//! assert_eq!(Max(100), largest);
+//! ```
+
+use std::mem;
+
+use ::rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+use crate::monoid::{All, Any, BitOr, First, Gcd, Last, Max, Min, Monoid, Product, Sum, Xor};
+use crate::semigroup::Semigroup;
+
+/// Extension methods for any [ParallelIterator] over [Monoid]s.
+pub trait ParallelCombine: ParallelIterator {
+    /// Combines all the elements, giving [Monoid::empty] for an empty iterator.
+    fn par_combine_all(self) -> Self::Item
+    where
+        Self::Item: Monoid,
+    {
+        self.reduce(Self::Item::empty, Semigroup::combine)
+    }
+
+    /// Maps every element into a monoid with `f` and combines the results.
+    fn par_fold_map<M, F>(self, f: F) -> M
+    where
+        M: Monoid + Send,
+        F: Fn(Self::Item) -> M + Sync + Send,
+    {
+        self.map(f).reduce(M::empty, Semigroup::combine)
+    }
+}
+
+impl<I: ParallelIterator> ParallelCombine for I {}
+
+macro_rules! impl_parallel {
+    (
+        $($t:ident),* $(,)?
+    ) => {
+        $(
+            impl<T> FromParallelIterator<$t<T>> for $t<T>
+            where
+                $t<T>: Monoid + Send,
+            {
+                fn from_par_iter<I>(par_iter: I) -> Self
+                where
+                    I: IntoParallelIterator<Item = $t<T>>,
+                {
+                    par_iter.into_par_iter().par_combine_all()
+                }
+            }
+
+            impl<T> ParallelExtend<$t<T>> for $t<T>
+            where
+                $t<T>: Monoid + Send,
+            {
+                fn par_extend<I>(&mut self, par_iter: I)
+                where
+                    I: IntoParallelIterator<Item = $t<T>>,
+                {
+                    let rest = par_iter.into_par_iter().par_combine_all();
+                    *self = mem::replace(self, Self::empty()).combine(rest);
+                }
+            }
+        )*
+    };
+}
+
+impl_parallel!(Sum, Product, Min, Max, First, Last, BitOr, Gcd, Xor);
+
+macro_rules! impl_parallel_bool {
+    (
+        $($t:ident),* $(,)?
+    ) => {
+        $(
+            impl FromParallelIterator<$t> for $t {
+                fn from_par_iter<I>(par_iter: I) -> Self
+                where
+                    I: IntoParallelIterator<Item = $t>,
+                {
+                    par_iter.into_par_iter().par_combine_all()
+                }
+            }
+
+            impl ParallelExtend<$t> for $t {
+                fn par_extend<I>(&mut self, par_iter: I)
+                where
+                    I: IntoParallelIterator<Item = $t>,
+                {
+                    let rest = par_iter.into_par_iter().par_combine_all();
+                    *self = mem::replace(self, Self::empty()).combine(rest);
+                }
+            }
+        )*
+    };
+}
+
+impl_parallel_bool!(Any, All);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ::rayon::prelude::*;
+    use quickcheck_macros::quickcheck;
+
+    fn sequential<M: Monoid>(values: Vec<M>) -> M {
+        values.into_iter().fold(M::empty(), Semigroup::combine)
+    }
+
+    #[quickcheck]
+    fn sum_is_the_same_as_a_sequential_fold(values: Vec<u32>) -> bool {
+        let values = values
+            .into_iter()
+            .map(|x| Sum(x as u64))
+            .collect::<Vec<_>>();
+
+        values.par_iter().copied().par_combine_all() == sequential(values.clone())
+            && values.par_iter().copied().collect::<Sum<u64>>() == sequential(values)
+    }
+
+    #[quickcheck]
+    fn max_is_the_same_as_a_sequential_fold(values: Vec<i32>) -> bool {
+        let parallel = values.par_iter().par_fold_map(|&x| Max(x));
+
+        parallel == sequential(values.into_iter().map(Max).collect())
+    }
+
+    #[quickcheck]
+    fn first_keeps_the_order(values: Vec<Option<u8>>) -> bool {
+        let parallel = values
+            .par_iter()
+            .map(|&x| First::from(x))
+            .collect::<First<u8>>();
+
+        parallel == sequential(values.into_iter().map(First::from).collect())
+    }
+
+    #[quickcheck]
+    fn vec_keeps_the_order(values: Vec<Vec<u8>>) -> bool {
+        values.clone().into_par_iter().par_combine_all() == sequential(values)
+    }
+
+    #[quickcheck]
+    fn par_extend_combines_onto_the_existing_value(start: u8, values: Vec<u8>) -> bool {
+        let mut total = Sum(start as u64);
+        total.par_extend(values.par_iter().map(|&x| Sum(x as u64)));
+
+        total == Sum(start as u64 + values.iter().map(|&x| x as u64).sum::<u64>())
+    }
+
+    #[test]
+    fn empty_iterator_gives_the_identity() {
+        let all = Vec::<All>::new().into_par_iter().collect::<All>();
+
+        assert_eq!(All::empty(), all);
+        assert_eq!(
+            Sum(0),
+            Vec::<Sum<i32>>::new().into_par_iter().par_combine_all()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Parallel folding of [Monoid]s with nothing but the standard library.
+//!
+//! A slice is cut into chunks which are mapped into a monoid on scoped worker threads. Every worker gets a run of
+//! neighbouring chunks and the partial results are combined in the order of the slice, so monoids that aren't
+//! commutative like [First](crate::First), [Last](crate::Last) or [String] give the same result as a sequential fold.
+
+use std::num::NonZeroUsize;
+use std::panic;
+use std::thread;
+
+use crate::monoid::Monoid;
+
+/// Maps every `chunk_size` long chunk of `slice` into a monoid with `map` and combines the results in order, using as
+/// many threads as [thread::available_parallelism] reports.
+///
+/// # Panics
+/// If `chunk_size` is zero or if `map` panics.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::parallel;
+///
+/// let words = ["fold", "ing", " in ", "par", "allel"];
+/// let sentence = parallel::reduce_chunks(&words, 2, |chunk| chunk.concat());
+/// assert_eq!("folding in parallel", sentence);
+///
+/// let numbers = (1..=1000u64).collect::<Vec<_>>();
+/// let total = parallel::reduce_chunks(&numbers, 64, |chunk| Sum(chunk.iter().sum::<u64>()));
+/// assert_eq!(Sum(500500), total);
+/// ```
+pub fn reduce_chunks<T, M, F>(slice: &[T], chunk_size: usize, map: F) -> M
+where
+    T: Sync,
+    M: Monoid + Send,
+    F: Fn(&[T]) -> M + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    reduce_chunks_with_threads(slice, chunk_size, threads, map)
+}
+
+/// The same as [reduce_chunks] but with at most `threads` worker threads.
+///
+/// # Panics
+/// If `chunk_size` or `threads` is zero or if `map` panics.
+pub fn reduce_chunks_with_threads<T, M, F>(
+    slice: &[T],
+    chunk_size: usize,
+    threads: usize,
+    map: F,
+) -> M
+where
+    T: Sync,
+    M: Monoid + Send,
+    F: Fn(&[T]) -> M + Sync,
+{
+    assert!(chunk_size > 0, "chunk size must be greater than zero");
+    assert!(threads > 0, "there has to be at least one thread");
+
+    let chunks = slice.chunks(chunk_size).collect::<Vec<_>>();
+    if chunks.is_empty() {
+        return M::empty();
+    }
+
+    let per_thread = chunks.len().div_ceil(threads);
+    let map = &map;
+
+    thread::scope(|scope| {
+        let workers = chunks
+            .chunks(per_thread)
+            .map(|run| {
+                scope.spawn(move || {
+                    run.iter()
+                        .fold(M::empty(), |acc, chunk| acc.combine(map(chunk)))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .fold(M::empty(), |acc, partial| acc.combine(partial))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{First, Last, Sum};
+    use crate::semigroup::Semigroup;
+
+    use quickcheck_macros::quickcheck;
+    use test_case::test_case;
+
+    #[quickcheck]
+    fn string_keeps_the_order(words: Vec<String>, chunk_size: u8, threads: u8) -> bool {
+        let chunk_size = chunk_size as usize % 5 + 1;
+        let threads = threads as usize % 6 + 1;
+
+        reduce_chunks_with_threads(&words, chunk_size, threads, |chunk| chunk.concat())
+            == words.concat()
+    }
+
+    #[quickcheck]
+    fn first_and_last_keep_the_order(values: Vec<Option<u8>>, chunk_size: u8, threads: u8) -> bool {
+        let chunk_size = chunk_size as usize % 5 + 1;
+        let threads = threads as usize % 6 + 1;
+
+        let parallel = reduce_chunks_with_threads(&values, chunk_size, threads, |chunk| {
+            chunk
+                .iter()
+                .map(|&x| (First::<u8>::from(x), Last::<u8>::from(x)))
+                .fold(Monoid::empty(), Semigroup::combine)
+        });
+        let sequential = values
+            .iter()
+            .map(|&x| (First::<u8>::from(x), Last::<u8>::from(x)))
+            .fold(Monoid::empty(), Semigroup::combine);
+
+        parallel == sequential
+    }
+
+    #[test_case(1 ; "one thread")]
+    #[test_case(3 ; "fewer threads than chunks")]
+    #[test_case(64 ; "more threads than chunks")]
+    fn sum_of_a_range(threads: usize) {
+        let numbers = (1..=100u64).collect::<Vec<_>>();
+        let total = reduce_chunks_with_threads(&numbers, 7, threads, |chunk| {
+            Sum(chunk.iter().sum::<u64>())
+        });
+
+        assert_eq!(Sum(5050), total);
+    }
+
+    #[test]
+    fn empty_slice_gives_the_identity() {
+        assert_eq!(
+            Sum(0),
+            reduce_chunks(&[] as &[u32], 4, |chunk| Sum(chunk.len()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size")]
+    fn zero_chunk_size_panics() {
+        reduce_chunks(&[1, 2, 3], 0, |chunk| Sum(chunk.len()));
+    }
+}
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::monoid::Monoid;
+use crate::semigroup::Semigroup;
+
+/// A type that can be built from layers of partial values, like a configuration that is read from defaults, files
+/// and the command line, where every layer only sets some of the fields.
+///
+/// The partial type is a [Monoid], so the layers can be combined in any grouping before the value is built, and a
+/// whole value converts into a partial one where every field is set. With the `derive` feature this is usually
+/// derived with `#[derive(Partial)]`.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::partial::MissingFields;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Port(u16);
+///
+/// impl Partial for Port {
+///     type Partial = Last<u16>;
+///
+///     fn from_partial(partial: Self::Partial) -> Result<Self, MissingFields> {
+///         partial.0.map(Port).ok_or_else(|| MissingFields::from("port"))
+///     }
+/// }
+///
+/// impl From<Port> for Last<u16> {
+///     fn from(port: Port) -> Self {
+///         Last::from(port.0)
+///     }
+/// }
+///
+/// let layers = Last::from(80).combine(Last::from(8080));
+/// assert_eq!(Ok(Port(8080)), Port::from_partial(layers));
+/// assert_eq!(Err(MissingFields::from("port")), Port::from_partial(Last::empty()));
+/// ```
+pub trait Partial: Sized {
+    type Partial: Monoid + From<Self>;
+
+    /// Builds the value, or gives back every field that is missing from the partial value.
+    fn from_partial(partial: Self::Partial) -> Result<Self, MissingFields>;
+}
+
+/// The paths of every field that was missing when a [Partial] value was built, like `server.port` for the field
+/// `port` of a nested `server`.
+///
+/// Missing fields are collected instead of stopping at the first one, combining two of them keeps the fields of both.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MissingFields(Vec<String>);
+
+impl MissingFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, path: impl Into<String>) {
+        self.0.push(path.into());
+    }
+
+    /// Adds the missing fields of a nested value with `prefix` in front of their paths.
+    pub fn nested(&mut self, prefix: &str, missing: MissingFields) {
+        self.0.extend(
+            missing
+                .0
+                .into_iter()
+                .map(|path| format!("{}.{}", prefix, path)),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl From<&str> for MissingFields {
+    fn from(path: &str) -> Self {
+        Self(vec![path.to_owned()])
+    }
+}
+
+impl Semigroup for MissingFields {
+    fn combine(mut self, rhs: Self) -> Self {
+        self.0.extend(rhs.0);
+        self
+    }
+}
+
+impl fmt::Display for MissingFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths = self
+            .0
+            .iter()
+            .map(|path| format!("`{}`", path))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.0.len() {
+            1 => write!(f, "missing field {}", paths),
+            _ => write!(f, "missing fields {}", paths),
+        }
+    }
+}
+
+impl Error for MissingFields {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_paths_are_prefixed() {
+        let mut inner = MissingFields::from("port");
+        inner.push("host");
+
+        let mut missing = MissingFields::from("name");
+        missing.nested("server", inner);
+
+        assert_eq!(missing.paths(), ["name", "server.port", "server.host"]);
+    }
+
+    #[test]
+    fn combine_keeps_both_sides_in_order() {
+        let missing = MissingFields::from("a")
+            .combine(MissingFields::empty())
+            .combine("b".into());
+
+        assert_eq!(missing.paths(), ["a", "b"]);
+        assert_eq!(2, missing.len());
+    }
+
+    #[test]
+    fn display_lists_every_field() {
+        let mut missing = MissingFields::from("name");
+        assert_eq!("missing field `name`", missing.to_string());
+
+        missing.push("server.port");
+        assert_eq!("missing fields `name`, `server.port`", missing.to_string());
+    }
+}
@@ -8,6 +8,14 @@ pub trait Semigroup {
     fn combine(self, rhs: Self) -> Self;
 }
 
+/// A marker for semigroups where combining a value with itself gives back the same value.
+///
+/// `x.combine(x) == x`
+///
+/// Overlapping ranges can then be combined without counting anything twice, which is what the
+/// [SparseTable](crate::collections::SparseTable) relies on.
+pub trait Idempotent: Semigroup {}
+
 /// This is just a small convienience macro to chain several combines together. Everthing after the first expression
 /// has to have a From<T> implementation for that type.
 ///
@@ -48,6 +56,8 @@ impl<T: Semigroup> Semigroup for Option<T> {
     }
 }
 
+impl<T: Idempotent> Idempotent for Option<T> {}
+
 /// Returns the first Result if it's an Ok variant, otherwise returns the second
 ///
 /// # Examples
@@ -103,6 +113,8 @@ impl Semigroup for Ordering {
     }
 }
 
+impl Idempotent for Ordering {}
+
 impl Semigroup for String {
     fn combine(self, rhs: Self) -> Self {
         self + &rhs
@@ -118,11 +130,13 @@ impl<T> Semigroup for Vec<T> {
 
 impl<T: Eq + std::hash::Hash> Semigroup for std::collections::HashSet<T> {
     fn combine(mut self, rhs: Self) -> Self {
-        self.extend(rhs.into_iter());
+        self.extend(rhs);
         self
     }
 }
 
+impl<T: Eq + std::hash::Hash> Idempotent for std::collections::HashSet<T> {}
+
 macro_rules! impl_semigroup_with_addition {
     ( $($x:ty),* ) => {
         $(
@@ -139,12 +153,38 @@ impl_semigroup_with_addition!(
     usize, isize, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64
 );
 
+// Tuples combine element-wise, so several semigroups can be aggregated in a single pass.
+macro_rules! impl_semigroup_for_tuple {
+    ( $( ($($t:ident $i:tt),+) ),* $(,)? ) => {
+        $(
+            impl<$($t: Semigroup),+> Semigroup for ($($t,)+) {
+                fn combine(self, rhs: Self) -> Self {
+                    ($(self.$i.combine(rhs.$i),)+)
+                }
+            }
+
+            impl<$($t: Idempotent),+> Idempotent for ($($t,)+) {}
+        )*
+    };
+}
+
+impl_semigroup_for_tuple!(
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5),
+);
+
 impl<T> Semigroup for PhantomData<T> {
     fn combine(self, _rhs: Self) -> Self {
         self
     }
 }
 
+impl<T> Idempotent for PhantomData<T> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +219,14 @@ mod tests {
         x.combine(y.combine(z)) == x.combine(y).combine(z)
     }
 
+    #[quickcheck]
+    fn tuple_associativity_property(x: (u8, String), y: (u8, String), z: (u8, String)) -> bool {
+        let widen = |(n, s): (u8, String)| (n as u16, s);
+        let (x, y, z) = (widen(x), widen(y), widen(z));
+
+        x.clone().combine(y.clone().combine(z.clone())) == x.combine(y).combine(z)
+    }
+
     #[test]
     fn option_combine_macro() {
         let sum: Option<Sum<i32>> = crate::combine!(
@@ -196,6 +244,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::let_and_return)]
     fn combine_macro() {
         let x = crate::combine! {
             Last::from(53), None, 42, {let b = None; b},
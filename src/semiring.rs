@@ -0,0 +1,202 @@
+use num_traits::{Bounded, CheckedAdd, Zero};
+
+/// A semiring is two monoids over the same type, addition and multiplication, where multiplication
+/// distributes over addition and [Semiring::zero] annihilates under multiplication.
+///
+/// The usual numbers under `+` and `*` are semirings, but so are booleans under `||` and `&&` or the
+/// tropical semirings [MinPlus] and [MaxPlus]. Anything built on top of a semiring, like
+/// [Matrix](crate::matrix::Matrix), works with all of them.
+pub trait Semiring: Clone {
+    /// The identity of [Semiring::plus].
+    fn zero() -> Self;
+
+    /// The identity of [Semiring::times].
+    fn one() -> Self;
+
+    fn plus(self, rhs: Self) -> Self;
+
+    fn times(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_semiring_with_arithmetic {
+    ( $( $x:ty: $zero:expr, $one:expr );* $(;)? ) => {
+        $(
+            impl Semiring for $x {
+                fn zero() -> Self {
+                    $zero
+                }
+
+                fn one() -> Self {
+                    $one
+                }
+
+                fn plus(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn times(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_semiring_with_arithmetic!(
+    usize: 0, 1; isize: 0, 1; u8: 0, 1; i8: 0, 1; u16: 0, 1; i16: 0, 1; u32: 0, 1; i32: 0, 1;
+    u64: 0, 1; i64: 0, 1; u128: 0, 1; i128: 0, 1; f32: 0.0, 1.0; f64: 0.0, 1.0;
+);
+
+/// The boolean semiring with `||` as addition and `&&` as multiplication.
+impl Semiring for bool {
+    fn zero() -> Self {
+        false
+    }
+
+    fn one() -> Self {
+        true
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        self || rhs
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        self && rhs
+    }
+}
+
+/// The bound that a sum overflowed past, which is the direction of the weight that was added.
+fn saturated<T: PartialOrd + Bounded + Zero>(added: T) -> T {
+    if added < T::zero() {
+        T::min_value()
+    } else {
+        T::max_value()
+    }
+}
+
+/// The tropical min-plus semiring, addition is the minimum and multiplication is the sum.
+///
+/// [Bounded::max_value] stands in for infinity and is the [Semiring::zero], it is absorbing under
+/// [Semiring::times] so that unreachable paths stay unreachable. A sum of weights that is too large
+/// for `T` is infinite as well.
+/// ```
+/// # use partial_functional::semiring::{MinPlus, Semiring};
+/// assert_eq!(MinPlus(3), MinPlus(3).plus(MinPlus(5)));
+/// assert_eq!(MinPlus(8), MinPlus(3).times(MinPlus(5)));
+/// assert_eq!(MinPlus::zero(), MinPlus(3).times(MinPlus::<u32>::zero()));
+/// assert_eq!(MinPlus::zero(), MinPlus(u32::MAX - 1).times(MinPlus(2)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinPlus<T>(pub T);
+
+impl<T> From<T> for MinPlus<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Copy + PartialOrd + Bounded + Zero + CheckedAdd> Semiring for MinPlus<T> {
+    fn zero() -> Self {
+        Self(T::max_value())
+    }
+
+    fn one() -> Self {
+        Self(T::zero())
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        if rhs.0 < self.0 {
+            rhs
+        } else {
+            self
+        }
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        if self == Self::zero() || rhs == Self::zero() {
+            return Self::zero();
+        }
+
+        Self(self.0.checked_add(&rhs.0).unwrap_or_else(|| saturated(rhs.0)))
+    }
+}
+
+/// The tropical max-plus semiring, addition is the maximum and multiplication is the sum.
+///
+/// [Bounded::min_value] stands in for negative infinity and is the [Semiring::zero]. A sum of weights
+/// that is too small for `T` is negative infinity as well, and one that is too large saturates at
+/// [Bounded::max_value].
+/// ```
+/// # use partial_functional::semiring::{MaxPlus, Semiring};
+/// assert_eq!(MaxPlus(5), MaxPlus(3).plus(MaxPlus(5)));
+/// assert_eq!(MaxPlus(8), MaxPlus(3).times(MaxPlus(5)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaxPlus<T>(pub T);
+
+impl<T> From<T> for MaxPlus<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Copy + PartialOrd + Bounded + Zero + CheckedAdd> Semiring for MaxPlus<T> {
+    fn zero() -> Self {
+        Self(T::min_value())
+    }
+
+    fn one() -> Self {
+        Self(T::zero())
+    }
+
+    fn plus(self, rhs: Self) -> Self {
+        if rhs.0 > self.0 {
+            rhs
+        } else {
+            self
+        }
+    }
+
+    fn times(self, rhs: Self) -> Self {
+        if self == Self::zero() || rhs == Self::zero() {
+            return Self::zero();
+        }
+
+        Self(self.0.checked_add(&rhs.0).unwrap_or_else(|| saturated(rhs.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn min_plus_distributes_over_plus(x: u8, y: u8, z: u8) -> bool {
+        let (x, y, z) = (MinPlus(x as u32), MinPlus(y as u32), MinPlus(z as u32));
+
+        x.times(y.plus(z)) == x.times(y).plus(x.times(z))
+    }
+
+    #[quickcheck]
+    fn max_plus_distributes_over_plus(x: u8, y: u8, z: u8) -> bool {
+        let (x, y, z) = (MaxPlus(x as i32), MaxPlus(y as i32), MaxPlus(z as i32));
+
+        x.times(y.plus(z)) == x.times(y).plus(x.times(z))
+    }
+
+    #[quickcheck]
+    fn zero_annihilates_min_plus(x: u32) -> bool {
+        MinPlus(x).times(MinPlus::zero()) == MinPlus::zero()
+    }
+
+    #[test]
+    fn overflowing_weights_saturate() {
+        assert_eq!(MinPlus::zero(), MinPlus(u64::MAX - 1).times(MinPlus(u64::MAX - 1)));
+        assert_eq!(MinPlus(i8::MIN), MinPlus(-100i8).times(MinPlus(-100)));
+        assert_eq!(MaxPlus::zero(), MaxPlus(-100i8).times(MaxPlus(-100)));
+        assert_eq!(MaxPlus(i8::MAX), MaxPlus(100i8).times(MaxPlus(100)));
+    }
+}
@@ -0,0 +1,172 @@
+//! Folding asynchronous [Stream]s into [Monoid]s, enabled by the `futures` feature.
+
+use std::future::{self, Future};
+
+use futures::stream::{Stream, StreamExt};
+
+use crate::monoid::Monoid;
+use crate::semigroup::Semigroup;
+
+/// Extension methods for any [Stream], in the same spirit as [StreamExt].
+///
+/// # Examples
+/// ```
+/// use futures::{executor, stream};
+/// use partial_functional::prelude::*;
+///
+/// let total = executor::block_on(stream::iter(1..=4).fold_map(Product));
+/// assert_eq!(Product(24), total);
+/// ```
+pub trait MonoidStreamExt: Stream {
+    /// Combines all the items of the stream, giving [Monoid::empty] for an empty stream.
+    fn combine_all(self) -> impl Future<Output = Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Monoid,
+    {
+        self.fold(Self::Item::empty(), |acc, x| future::ready(acc.combine(x)))
+    }
+
+    /// Maps every item into a monoid with `f` and combines the results.
+    fn fold_map<M, F>(self, mut f: F) -> impl Future<Output = M>
+    where
+        Self: Sized,
+        M: Monoid,
+        F: FnMut(Self::Item) -> M,
+    {
+        self.fold(M::empty(), move |acc, x| future::ready(acc.combine(f(x))))
+    }
+
+    /// Maps every item into a future with `f` and combines their outputs, running up to `n` of the futures at the
+    /// same time. The outputs are still combined in the order of the stream, no matter which future finishes first.
+    ///
+    /// # Panics
+    /// If `n` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use futures::{executor, stream};
+    /// use partial_functional::prelude::*;
+    ///
+    /// async fn fetch(page: u32) -> Vec<u32> {
+    ///     vec![page * 10, page * 10 + 1]
+    /// }
+    ///
+    /// let pages = executor::block_on(stream::iter(1..=3).buffered_fold_map(2, fetch));
+    /// assert_eq!(vec![10, 11, 20, 21, 30, 31], pages);
+    /// ```
+    fn buffered_fold_map<M, Fut, F>(self, n: usize, f: F) -> impl Future<Output = M>
+    where
+        Self: Sized,
+        M: Monoid,
+        Fut: Future<Output = M>,
+        F: FnMut(Self::Item) -> Fut,
+    {
+        assert!(n > 0, "at least one future has to be able to run");
+
+        self.map(f).buffered(n).combine_all()
+    }
+}
+
+impl<S: Stream> MonoidStreamExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::monoid::{First, Last, Max, Sum};
+
+    use std::cell::Cell;
+    use std::task::Poll;
+
+    use futures::channel::oneshot;
+    use futures::{executor, join, stream};
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn combine_all_is_the_same_as_a_fold(values: Vec<String>) -> bool {
+        let streamed = executor::block_on(stream::iter(values.clone()).combine_all());
+
+        streamed == values.concat()
+    }
+
+    #[quickcheck]
+    fn fold_map_is_the_same_as_a_fold(values: Vec<i32>) -> bool {
+        let streamed = executor::block_on(stream::iter(values.clone()).fold_map(Max));
+
+        streamed
+            == values
+                .into_iter()
+                .map(Max)
+                .fold(Max::empty(), Semigroup::combine)
+    }
+
+    #[quickcheck]
+    fn buffered_fold_map_is_the_same_as_a_fold(values: Vec<Option<u8>>, n: u8) -> bool {
+        let n = n as usize % 4 + 1;
+        let to_monoid = |x: Option<u8>| {
+            (
+                First::<u8>::from(x),
+                Last::<u8>::from(x),
+                Sum(x.unwrap_or(0) as u32),
+            )
+        };
+
+        let streamed = executor::block_on(
+            stream::iter(values.clone()).buffered_fold_map(n, |x| future::ready(to_monoid(x))),
+        );
+
+        streamed
+            == values
+                .into_iter()
+                .map(to_monoid)
+                .fold(Monoid::empty(), Semigroup::combine)
+    }
+
+    #[test]
+    fn buffered_fold_map_keeps_the_order_when_futures_finish_backwards() {
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..5).map(|_| oneshot::channel::<String>()).unzip();
+
+        let fold = stream::iter(receivers).buffered_fold_map(5, |rx| async { rx.await.unwrap() });
+        let send = async {
+            for (i, tx) in senders.into_iter().enumerate().rev() {
+                tx.send(i.to_string()).unwrap();
+            }
+        };
+
+        let (result, ()) = executor::block_on(async { join!(fold, send) });
+
+        assert_eq!("01234", result);
+    }
+
+    #[test]
+    fn buffered_fold_map_runs_at_most_n_futures() {
+        let running = Cell::new(0);
+        let most = Cell::new(0);
+
+        let total = executor::block_on(stream::iter(0..20).buffered_fold_map(3, |x| {
+            let (running, most) = (&running, &most);
+            async move {
+                running.set(running.get() + 1);
+                most.set(most.get().max(running.get()));
+                let mut yielded = false;
+                future::poll_fn(|cx| {
+                    if yielded {
+                        Poll::Ready(())
+                    } else {
+                        yielded = true;
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                })
+                .await;
+                running.set(running.get() - 1);
+                Sum(x)
+            }
+        }));
+
+        assert_eq!(Sum(190), total);
+        assert_eq!(3, most.get());
+    }
+}
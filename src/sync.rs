@@ -0,0 +1,264 @@
+//! Accumulating [Monoid]s from many threads at once.
+//!
+//! [SharedAccumulator] works for any monoid by spreading the values over a number of locked shards, so threads rarely
+//! wait on each other. The atomic accumulators like [AtomicSum] do the same for the most common monoids without any
+//! locking at all. All of them implement [Accumulator] so code can be written against either.
+//!
+//! Values from different threads are added in no particular order, which means the result is only well defined for
+//! commutative monoids like [Sum], [Max] or a [HashSet](std::collections::HashSet).
+
+use std::cell::Cell;
+use std::mem;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use crate::monoid::{All, Any, Max, Min, Monoid, Sum};
+
+/// Something that many threads can add values to through a shared reference.
+pub trait Accumulator<M: Monoid> {
+    /// Combines `value` onto the accumulated value.
+    fn add(&self, value: M);
+
+    /// Returns the combination of everything that has been added so far.
+    fn snapshot(&self) -> M;
+}
+
+/// An accumulator for any [Monoid] that can be shared between threads.
+///
+/// Every thread is handed one of the shards the first time it adds a value and keeps using it, values are then
+/// combined into that shard under its own lock. A snapshot locks each shard in turn and combines them.
+///
+/// # Examples
+/// ```
+/// use std::thread;
+///
+/// use partial_functional::prelude::*;
+/// use partial_functional::sync::{Accumulator, SharedAccumulator};
+///
+/// let hits = SharedAccumulator::new();
+/// thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| (0..100).for_each(|_| hits.add(Sum(1))));
+///     }
+/// });
+///
+/// assert_eq!(Sum(400), hits.snapshot());
+/// ```
+#[derive(Debug)]
+pub struct SharedAccumulator<M> {
+    shards: Box<[Mutex<M>]>,
+}
+
+impl<M: Monoid + Clone> SharedAccumulator<M> {
+    /// Creates an accumulator with one shard for every thread that [thread::available_parallelism] reports.
+    pub fn new() -> Self {
+        Self::with_shards(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+
+    /// Creates an accumulator with `shards` shards.
+    ///
+    /// # Panics
+    /// If `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "there has to be at least one shard");
+
+        Self {
+            shards: (0..shards).map(|_| Mutex::new(M::empty())).collect(),
+        }
+    }
+
+    /// Takes the accumulated value, leaving [Monoid::empty] in its place.
+    pub fn take(&self) -> M {
+        self.shards
+            .iter()
+            .map(|shard| mem::replace(&mut *lock(shard), M::empty()))
+            .fold(M::empty(), |acc, value| acc.combine(value))
+    }
+
+    pub fn into_inner(self) -> M {
+        self.shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .fold(M::empty(), |acc, value| acc.combine(value))
+    }
+
+    fn shard(&self) -> &Mutex<M> {
+        &self.shards[thread_slot() % self.shards.len()]
+    }
+}
+
+impl<M: Monoid + Clone> Accumulator<M> for SharedAccumulator<M> {
+    fn add(&self, value: M) {
+        let mut shard = lock(self.shard());
+        let acc = mem::replace(&mut *shard, M::empty());
+        *shard = acc.combine(value);
+    }
+
+    fn snapshot(&self) -> M {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).clone())
+            .fold(M::empty(), |acc, value| acc.combine(value))
+    }
+}
+
+impl<M: Monoid + Clone> Default for SharedAccumulator<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A value that panicked half way through a combine is still a valid value, so a poisoned lock is simply ignored.
+fn lock<M>(shard: &Mutex<M>) -> MutexGuard<'_, M> {
+    shard.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Hands out a number to every thread the first time it asks for one.
+fn thread_slot() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    thread_local! {
+        static SLOT: Cell<Option<usize>> = const { Cell::new(None) };
+    }
+
+    SLOT.with(|slot| match slot.get() {
+        Some(next) => next,
+        None => {
+            let next = NEXT.fetch_add(1, Ordering::Relaxed);
+            slot.set(Some(next));
+            next
+        }
+    })
+}
+
+macro_rules! impl_atomic {
+    (
+        $($(#[$attr:meta])* $name:ident($monoid:ty, $atomic:ty, $op:ident);)*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug)]
+            pub struct $name($atomic);
+
+            impl $name {
+                pub fn new() -> Self {
+                    Self(<$atomic>::new(<$monoid>::empty().0))
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl From<$monoid> for $name {
+                fn from(value: $monoid) -> Self {
+                    Self(<$atomic>::new(value.0))
+                }
+            }
+
+            impl Accumulator<$monoid> for $name {
+                fn add(&self, value: $monoid) {
+                    self.0.$op(value.0, Ordering::Relaxed);
+                }
+
+                fn snapshot(&self) -> $monoid {
+                    <$monoid>::from(self.0.load(Ordering::Relaxed))
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic! {
+    /// A lock free accumulator for a [Sum] of [u64]. Unlike [Sum] itself it wraps around on overflow instead of
+    /// panicking.
+    AtomicSum(Sum<u64>, AtomicU64, fetch_add);
+    /// A lock free accumulator for a [Max] of [u64].
+    AtomicMax(Max<u64>, AtomicU64, fetch_max);
+    /// A lock free accumulator for a [Min] of [u64].
+    AtomicMin(Min<u64>, AtomicU64, fetch_min);
+    /// A lock free accumulator for [Any].
+    AtomicAny(Any, AtomicBool, fetch_or);
+    /// A lock free accumulator for [All].
+    AtomicAll(All, AtomicBool, fetch_and);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use quickcheck_macros::quickcheck;
+
+    const THREADS: u64 = 8;
+    const PER_THREAD: u64 = 10_000;
+
+    fn stress<M, A>(accumulator: &A, value: impl Fn(u64) -> M + Sync) -> M
+    where
+        M: Monoid,
+        A: Accumulator<M> + Sync,
+    {
+        thread::scope(|scope| {
+            for t in 0..THREADS {
+                let value = &value;
+                scope.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        accumulator.add(value(t * PER_THREAD + i));
+                    }
+                });
+            }
+        });
+
+        (0..THREADS * PER_THREAD)
+            .map(value)
+            .fold(M::empty(), |acc, x| acc.combine(x))
+    }
+
+    macro_rules! stress_tests {
+        ( $($name:ident: $accumulator:expr, $value:expr;)* ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let accumulator = $accumulator;
+                    let sequential = stress(&accumulator, $value);
+
+                    assert_eq!(sequential, accumulator.snapshot());
+                }
+            )*
+        };
+    }
+
+    stress_tests! {
+        shared_sum: SharedAccumulator::with_shards(3), Sum;
+        shared_max_and_min: SharedAccumulator::new(), |x| (Max(x % 977), Min(x % 977 + 5));
+        shared_set: SharedAccumulator::new(), |x| HashSet::from([x % 100]);
+        atomic_sum: AtomicSum::new(), Sum;
+        atomic_max: AtomicMax::new(), |x| Max(x * 7 % 1013);
+        atomic_min: AtomicMin::new(), |x| Min(x * 7 % 1013 + 3);
+        atomic_any: AtomicAny::new(), |x| Any(x == 12_345);
+        atomic_all: AtomicAll::new(), |x| All(x != 54_321);
+    }
+
+    #[quickcheck]
+    fn take_leaves_the_identity(values: Vec<u32>) -> bool {
+        let accumulator = SharedAccumulator::with_shards(2);
+        values.iter().for_each(|&x| accumulator.add(Sum(x as u64)));
+
+        let total = accumulator.take();
+
+        total == Sum(values.iter().map(|&x| x as u64).sum()) && accumulator.into_inner() == Sum(0)
+    }
+
+    #[test]
+    fn empty_accumulators_give_the_identity() {
+        assert_eq!(Min(u64::MAX), AtomicMin::new().snapshot());
+        assert_eq!(All(true), AtomicAll::new().snapshot());
+        assert_eq!(Sum(0), SharedAccumulator::<Sum<u64>>::new().snapshot());
+    }
+}
@@ -0,0 +1,380 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::monoid::{All, Any, BitOr, First, Gcd, Last, Max, Min, Product, Sum, Xor};
+use crate::semigroup::Semigroup;
+
+/// Which sides of a combine end up in the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kept {
+    Left,
+    Right,
+    /// Both sides contributed, like the two terms of a sum, or the result is the same as either of them, like two
+    /// [Last]s without a value.
+    Both,
+    /// The sides are equal and the left one is given back, like a tie of [Min]. A side without any sources loses the
+    /// tie, so that combining with an untracked value doesn't drop the sources of the other side.
+    TieLeft,
+    /// The sides are equal and the right one is given back, like a tie of [Max].
+    TieRight,
+}
+
+/// A [Semigroup] that can tell which sides of a combine ended up in the result, so that [Tracked] knows which
+/// sources to keep.
+///
+/// [First] and [Last] keep the side that holds the value, [Max] and [Min] keep the side that wins the comparison and
+/// the arithmetic semigroups keep both sides.
+pub trait Provenance: Semigroup {
+    fn kept(&self, rhs: &Self) -> Kept;
+}
+
+impl<T> Provenance for First<T> {
+    fn kept(&self, rhs: &Self) -> Kept {
+        match (&self.0, &rhs.0) {
+            (Some(_), _) => Kept::Left,
+            (None, Some(_)) => Kept::Right,
+            (None, None) => Kept::Both,
+        }
+    }
+}
+
+impl<T> Provenance for Last<T> {
+    fn kept(&self, rhs: &Self) -> Kept {
+        match (&self.0, &rhs.0) {
+            (_, Some(_)) => Kept::Right,
+            (Some(_), None) => Kept::Left,
+            (None, None) => Kept::Both,
+        }
+    }
+}
+
+// `Ord::max` gives back the right hand side and `Ord::min` the left hand side when both are equal.
+impl<T: Ord> Provenance for Max<T> {
+    fn kept(&self, rhs: &Self) -> Kept {
+        match self.0.cmp(&rhs.0) {
+            Ordering::Less => Kept::Right,
+            Ordering::Equal => Kept::TieRight,
+            Ordering::Greater => Kept::Left,
+        }
+    }
+}
+
+impl<T: Ord> Provenance for Min<T> {
+    fn kept(&self, rhs: &Self) -> Kept {
+        match self.0.cmp(&rhs.0) {
+            Ordering::Less => Kept::Left,
+            Ordering::Equal => Kept::TieLeft,
+            Ordering::Greater => Kept::Right,
+        }
+    }
+}
+
+macro_rules! impl_both {
+    ( $($t:ident),* $(,)? ) => {
+        $(
+            impl<T> Provenance for $t<T> where $t<T>: Semigroup {
+                fn kept(&self, _rhs: &Self) -> Kept {
+                    Kept::Both
+                }
+            }
+        )*
+    };
+}
+
+impl_both!(Sum, Product, BitOr, Gcd, Xor);
+
+impl Provenance for Any {
+    fn kept(&self, _rhs: &Self) -> Kept {
+        Kept::Both
+    }
+}
+
+impl Provenance for All {
+    fn kept(&self, _rhs: &Self) -> Kept {
+        Kept::Both
+    }
+}
+
+/// A semigroup value along with the sources it came from, like the files, layers or environment variables that a
+/// configuration was merged from.
+///
+/// Combining keeps the sources of the side that won for semigroups like [Last], and of both sides for semigroups like
+/// [Sum], see [Provenance]. [Tracked::explain] shows the value with its sources.
+///
+/// # Examples
+/// ```
+/// use partial_functional::prelude::*;
+/// use partial_functional::tracked::{Origin, Tracked};
+///
+/// let port = Tracked::new(Last::from(80), Origin::Layer(0))
+///     .combine(Tracked::new(Last::from(8080), Origin::Env("APP_PORT".to_owned())))
+///     .combine(Tracked::new(Last(None), Origin::File("app.toml".into())));
+///
+/// assert_eq!(Last::from(8080), *port.value());
+/// assert_eq!("Last(Some(8080)) from $APP_PORT", port.explain().to_string());
+///
+/// let requests = Tracked::new(Sum(3), "monday").combine(Tracked::new(Sum(4), "tuesday"));
+/// assert_eq!("Sum(7) from monday, tuesday", requests.explain().to_string());
+/// ```
+///
+/// With the `derive` feature a struct of tracked fields can be derived, and every field explains itself.
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use partial_functional::prelude::*;
+/// use partial_functional::tracked::{Origin, Tracked};
+///
+/// #[derive(Debug, Default, Semigroup)]
+/// struct Totals {
+///     orders: Tracked<Sum<u32>, Origin>,
+///     largest: Tracked<Max<u32>, Origin>,
+/// }
+///
+/// fn totals(file: &str, orders: u32, largest: u32) -> Totals {
+///     Totals {
+///         orders: Tracked::new(Sum(orders), Origin::File(file.into())),
+///         largest: Tracked::new(Max(largest), Origin::File(file.into())),
+///     }
+/// }
+///
+/// let totals = totals("a.csv", 2, 10).combine(totals("b.csv", 3, 40));
+/// assert_eq!("Sum(5) from a.csv, b.csv", totals.orders.explain().to_string());
+/// assert_eq!("Max(40) from b.csv", totals.largest.explain().to_string());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tracked<T, S> {
+    value: T,
+    sources: Vec<S>,
+}
+
+impl<T, S> Tracked<T, S> {
+    pub fn new(value: T, source: S) -> Self {
+        Self {
+            value,
+            sources: vec![source],
+        }
+    }
+
+    /// A value that didn't come from any source.
+    pub fn untracked(value: T) -> Self {
+        Self {
+            value,
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The sources the value came from, in the order they were combined.
+    pub fn sources(&self) -> &[S] {
+        &self.sources
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn into_parts(self) -> (T, Vec<S>) {
+        (self.value, self.sources)
+    }
+
+    /// Shows the value along with the sources it came from, like `Last(Some(8080)) from $APP_PORT`.
+    pub fn explain(&self) -> Explanation<'_, T, S> {
+        Explanation(self)
+    }
+}
+
+impl<T: Default, S> Default for Tracked<T, S> {
+    fn default() -> Self {
+        Self::untracked(T::default())
+    }
+}
+
+impl<T: Provenance, S> Semigroup for Tracked<T, S> {
+    fn combine(mut self, mut rhs: Self) -> Self {
+        let sources = match self.value.kept(&rhs.value) {
+            Kept::Left => self.sources,
+            Kept::Right => rhs.sources,
+            Kept::TieLeft if !self.sources.is_empty() => self.sources,
+            Kept::TieRight if !rhs.sources.is_empty() => rhs.sources,
+            Kept::TieLeft | Kept::TieRight => self.sources.into_iter().chain(rhs.sources).collect(),
+            Kept::Both => {
+                self.sources.append(&mut rhs.sources);
+                self.sources
+            }
+        };
+
+        Self {
+            value: self.value.combine(rhs.value),
+            sources,
+        }
+    }
+}
+
+/// The report of [Tracked::explain].
+pub struct Explanation<'a, T, S>(&'a Tracked<T, S>);
+
+impl<T: fmt::Debug, S: fmt::Display> fmt::Display for Explanation<'_, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Tracked { value, sources } = self.0;
+        write!(f, "{:?}", value)?;
+
+        if sources.is_empty() {
+            return write!(f, " from no source");
+        }
+
+        write!(f, " from ")?;
+        for (i, source) in sources.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", source)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Where a value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Origin {
+    File(PathBuf),
+    /// The position of a layer, like the defaults at 0.
+    Layer(usize),
+    /// The name of an environment variable.
+    Env(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Layer(index) => write!(f, "layer {}", index),
+            Origin::Env(name) => write!(f, "${}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::monoid::Monoid;
+
+    fn last(value: Option<u8>, source: &'static str) -> Tracked<Last<u8>, &'static str> {
+        Tracked::new(Last(value), source)
+    }
+
+    #[test]
+    fn last_keeps_the_source_of_the_last_value() {
+        let merged = last(Some(1), "defaults")
+            .combine(last(Some(2), "file"))
+            .combine(last(None, "env"));
+
+        assert_eq!(Last(Some(2)), merged.into_inner());
+        assert_eq!(["file"], last(Some(1), "a").combine(last(Some(2), "file")).sources());
+        assert_eq!(["a", "b"], last(None, "a").combine(last(None, "b")).sources());
+    }
+
+    #[test]
+    fn first_keeps_the_source_of_the_first_value() {
+        let merged = Tracked::new(First(None), "a")
+            .combine(Tracked::new(First::<u8>::from(1), "b"))
+            .combine(Tracked::new(First::<u8>::from(2), "c"));
+
+        assert_eq!((First(Some(1)), vec!["b"]), merged.into_parts());
+    }
+
+    #[test]
+    fn max_and_min_keep_the_side_they_give_back() {
+        let max = Tracked::new(Max(3), "a").combine(Tracked::new(Max(3), "b"));
+        let min = Tracked::new(Min(3), "a").combine(Tracked::new(Min(3), "b"));
+        let larger = Tracked::new(Max(9), "a").combine(Tracked::new(Max(3), "b"));
+
+        assert_eq!(["b"], max.sources());
+        assert_eq!(["a"], min.sources());
+        assert_eq!(["a"], larger.sources());
+    }
+
+    #[test]
+    fn untracked_values_lose_ties() {
+        let max = Tracked::new(Max(0u32), "a").combine(Tracked::empty());
+        let min = Tracked::empty().combine(Tracked::new(Min(u32::MAX), "a"));
+
+        assert_eq!(["a"], max.sources());
+        assert_eq!(["a"], min.sources());
+    }
+
+    #[test]
+    fn sum_keeps_every_source_in_order() {
+        let total = ["a", "b", "c"]
+            .into_iter()
+            .map(|source| Tracked::new(Sum(1), source))
+            .fold(Tracked::empty(), Semigroup::combine);
+
+        assert_eq!(Sum(3), *total.value());
+        assert_eq!(["a", "b", "c"], total.sources());
+    }
+
+    #[test]
+    fn explain_lists_the_sources() {
+        let port = Tracked::new(Last::from(80), Origin::Layer(0))
+            .combine(Tracked::new(Last::from(8080), Origin::File("app.toml".into())));
+        let nothing = Tracked::<Last<u16>, Origin>::empty();
+        let any =
+            Tracked::new(Any(true), Origin::Env("A".to_owned())).combine(Tracked::new(Any(false), Origin::Layer(2)));
+
+        assert_eq!("Last(Some(8080)) from app.toml", port.explain().to_string());
+        assert_eq!("Last(None) from no source", nothing.explain().to_string());
+        assert_eq!("Any(true) from $A, layer 2", any.explain().to_string());
+    }
+
+    #[quickcheck]
+    fn tracking_doesnt_change_the_value(values: Vec<Option<u8>>) -> bool {
+        let tracked = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Tracked::new(Last(*value), i))
+            .fold(Tracked::empty(), Semigroup::combine);
+        let untracked = values
+            .iter()
+            .map(|value| Last(*value))
+            .fold(Last::empty(), Semigroup::combine);
+
+        // Without any value every layer is kept, since each of them gives the same result.
+        let sources = match values.iter().rposition(Option::is_some) {
+            Some(last_set) => vec![last_set],
+            None => (0..values.len()).collect(),
+        };
+        *tracked.value() == untracked && tracked.sources() == sources
+    }
+
+    #[quickcheck]
+    fn associativity_property(x: (u8, u8), y: (u8, u8), z: (u8, u8)) -> bool {
+        let max = |(value, source): (u8, u8)| Tracked::new(Max(value), source);
+
+        max(x).combine(max(y).combine(max(z))) == max(x).combine(max(y)).combine(max(z))
+    }
+
+    fn identity_holds<T: Provenance + Default + Clone + PartialEq>(x: Tracked<T, u8>) -> bool {
+        Tracked::empty().combine(x.clone()) == x && x.clone().combine(Tracked::empty()) == x
+    }
+
+    #[quickcheck]
+    fn identity_property(value: u8, source: u8, set: bool) -> bool {
+        let last = Last(Some(value).filter(|_| set));
+
+        identity_holds(Tracked::new(Max(value), source))
+            && identity_holds(Tracked::new(Max(0), source))
+            && identity_holds(Tracked::new(Min(value), source))
+            && identity_holds(Tracked::new(Min(u8::MAX), source))
+            && identity_holds(Tracked::new(last, source))
+            && identity_holds(Tracked::new(First(last.0), source))
+            && identity_holds(Tracked::new(Sum(value as u32), source))
+    }
+}